@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+
+use crate::Result;
+
+//命令行参数：子命令 + 若干 `--key value`，同一个key可以出现多次
+pub struct Args {
+    pub command: String,
+    options: HashMap<String, Vec<String>>,
+}
+
+impl Args {
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Self> {
+        let command = args.next().ok_or("missing command")?;
+        let mut options: HashMap<String, Vec<String>> = HashMap::new();
+        while let Some(arg) = args.next() {
+            let key = match arg.strip_prefix("--") {
+                Some(key) => key.to_string(),
+                None => return Err(format!("unexpected argument: {}", arg).into()),
+            };
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for --{}", key))?;
+            options.entry(key).or_default().push(value);
+        }
+        Ok(Args { command, options })
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.options
+            .get(key)
+            .and_then(|values| values.last())
+            .map(|value| value.as_str())
+    }
+
+    pub fn required(&self, key: &str) -> Result<&str> {
+        self.get(key)
+            .ok_or_else(|| format!("missing --{}", key).into())
+    }
+
+    pub fn all(&self, key: &str) -> Vec<&str> {
+        self.options
+            .get(key)
+            .map(|values| values.iter().map(|value| value.as_str()).collect())
+            .unwrap_or_default()
+    }
+}
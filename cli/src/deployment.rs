@@ -0,0 +1,128 @@
+use std::fs;
+use std::path::Path;
+
+use ckb_tool::ckb_types::{
+    bytes::Bytes,
+    core::{DepType, ScriptHashType},
+    packed::{CellDep, OutPoint, Script},
+    prelude::*,
+    H256,
+};
+use serde::Deserialize;
+use sourly_cat_sdk::CatDeployment;
+
+use crate::Result;
+
+//猫的type script对应的cell名字
+const SOURLY_CAT_CELL: &str = "sourly_cat";
+
+//deployment.toml中用到的部分
+#[derive(Deserialize)]
+struct DeploymentConfig {
+    #[serde(default)]
+    cells: Vec<CellConfig>,
+    #[serde(default)]
+    dep_groups: Vec<DepGroupConfig>,
+}
+
+#[derive(Deserialize)]
+struct CellConfig {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct DepGroupConfig {
+    name: String,
+    cells: Vec<String>,
+}
+
+//capsule deploy 写在migrations目录下的部署结果
+#[derive(Deserialize)]
+struct Migration {
+    cell_recipes: Vec<CellRecipe>,
+    #[serde(default)]
+    dep_group_recipes: Vec<DepGroupRecipe>,
+}
+
+#[derive(Deserialize)]
+struct CellRecipe {
+    name: String,
+    tx_hash: H256,
+    index: u32,
+    data_hash: H256,
+    type_id: Option<H256>,
+}
+
+#[derive(Deserialize)]
+struct DepGroupRecipe {
+    name: String,
+    tx_hash: H256,
+    index: u32,
+}
+
+fn out_point(tx_hash: &H256, index: u32) -> OutPoint {
+    OutPoint::new_builder()
+        .tx_hash(tx_hash.pack())
+        .index(index.pack())
+        .build()
+}
+
+//交易只需要sourly_cat的代码：它在deployment.toml的某个dep group中时引用这个dep group，
+//否则引用cell本身，out point从部署结果中查找；lock需要的cell dep由签名的工具补充。
+//猫的type script使用sourly_cat的type id，没有时使用data hash
+pub fn load(deployment: &Path, migration: &Path, type_args: Bytes) -> Result<CatDeployment> {
+    let config: DeploymentConfig = toml::from_str(&fs::read_to_string(deployment)?)?;
+    let migration: Migration = serde_json::from_str(&fs::read_to_string(migration)?)?;
+
+    if !config.cells.iter().any(|cell| cell.name == SOURLY_CAT_CELL) {
+        return Err(format!("cell {} is not in the deployment", SOURLY_CAT_CELL).into());
+    }
+    let find_cell = |name: &str| {
+        migration
+            .cell_recipes
+            .iter()
+            .find(|recipe| recipe.name == name)
+            .ok_or_else(|| format!("cell {} is not deployed", name))
+    };
+
+    let dep_group = config
+        .dep_groups
+        .iter()
+        .find(|dep_group| dep_group.cells.iter().any(|cell| cell == SOURLY_CAT_CELL));
+    let cell_dep = match dep_group {
+        Some(dep_group) => {
+            let recipe = migration
+                .dep_group_recipes
+                .iter()
+                .find(|recipe| recipe.name == dep_group.name)
+                .ok_or_else(|| format!("dep group {} is not deployed", dep_group.name))?;
+            CellDep::new_builder()
+                .out_point(out_point(&recipe.tx_hash, recipe.index))
+                .dep_type(DepType::DepGroup.into())
+                .build()
+        }
+        None => {
+            let recipe = find_cell(SOURLY_CAT_CELL)?;
+            CellDep::new_builder()
+                .out_point(out_point(&recipe.tx_hash, recipe.index))
+                .dep_type(DepType::Code.into())
+                .build()
+        }
+    };
+
+    let recipe = find_cell(SOURLY_CAT_CELL)?;
+    let (code_hash, hash_type) = match &recipe.type_id {
+        Some(type_id) => (type_id, ScriptHashType::Type),
+        None => (&recipe.data_hash, ScriptHashType::Data),
+    };
+    let type_script = Script::new_builder()
+        .code_hash(code_hash.pack())
+        .hash_type(hash_type.into())
+        .args(type_args.pack())
+        .build();
+
+    Ok(CatDeployment {
+        cell_deps: vec![cell_dep],
+        type_script,
+    })
+}
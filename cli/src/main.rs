@@ -0,0 +1,266 @@
+//! sourly-cat: 离线构造sourly_cat交易的命令行工具
+//!
+//! 输出的交易没有签名、手续费和找零，需要用其它工具补充后再发送
+
+mod args;
+mod deployment;
+
+use std::fs;
+use std::path::Path;
+
+use ckb_tool::ckb_jsonrpc_types as json_types;
+use ckb_tool::ckb_types::{
+    bytes::Bytes,
+    core::{DepType, ScriptHashType},
+    packed::{CellDep, CellInput, CellOutput, OutPoint, Script},
+    prelude::*,
+    H256,
+};
+use serde::Deserialize;
+use sourly_cat_sdk::{
+    BuiltTx, CatCell, CatDeployment, Config, FightBuilder, MintBuilder, NFTData, TransferBuilder,
+};
+
+use args::Args;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+//新猫默认的容量，单位shannons
+const DEFAULT_CAT_CAPACITY: u64 = 200_0000_0000;
+
+//type args中配置cell的type hash的位置：owner lock hash 32 bytes + 改名费 4 bytes之后
+const CONFIG_TYPE_HASH_OFFSET: usize = 36;
+const CONFIG_TYPE_HASH_LEN: usize = 32;
+
+const USAGE: &str = "\
+usage: sourly-cat <command> [options]
+
+common options:
+  --deployment <path>     capsule deployment file (default: deployment.toml)
+  --migration <path>      capsule migration file written by `capsule deploy`
+  --type-args <hex>       args of the sourly_cat type script
+  --config <config.json>  the config cell, required when the type args reference one
+  --out <path>            where to write the unsigned transaction JSON
+
+commands:
+  mint      --owner-input <tx_hash:index> --name <name> --lock <lock> [--capacity <shannons>]
+  transfer  --cat <cat.json> --lock <lock>   (repeat --cat/--lock to transfer several cats)
+  fight     --cat <cat.json> --cat <cat.json> [--max-rounds <n>]
+
+<lock> is `code_hash:type|data:args`; <cat.json> and <config.json> are
+{\"out_point\", \"output\", \"data\"} in the CKB RPC JSON format.";
+
+//一只猫或者配置cell，字段与CKB RPC的JSON格式一致
+#[derive(Deserialize)]
+struct CellFile {
+    out_point: json_types::OutPoint,
+    output: json_types::CellOutput,
+    data: json_types::JsonBytes,
+}
+
+fn parse_hex(hex: &str) -> Result<Vec<u8>> {
+    let hex = hex.trim_start_matches("0x");
+    let mut buf = vec![0u8; hex.len() / 2];
+    faster_hex::hex_decode(hex.as_bytes(), &mut buf)
+        .map_err(|_| format!("invalid hex: {}", hex))?;
+    Ok(buf)
+}
+
+fn parse_hash(hex: &str) -> Result<[u8; 32]> {
+    let bytes = parse_hex(hex)?;
+    if bytes.len() != 32 {
+        return Err(format!("invalid hash: {}", hex).into());
+    }
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&bytes);
+    Ok(hash)
+}
+
+fn parse_out_point(value: &str) -> Result<OutPoint> {
+    let mut parts = value.split(':');
+    let (tx_hash, index) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(tx_hash), Some(index), None) => (tx_hash, index),
+        _ => return Err(format!("invalid out point: {}", value).into()),
+    };
+    Ok(OutPoint::new_builder()
+        .tx_hash(parse_hash(tx_hash)?.pack())
+        .index(index.parse::<u32>()?.pack())
+        .build())
+}
+
+fn parse_lock(value: &str) -> Result<Script> {
+    let parts: Vec<&str> = value.split(':').collect();
+    if parts.len() != 3 {
+        return Err(format!("invalid lock: {}", value).into());
+    }
+    let hash_type = match parts[1] {
+        "type" => ScriptHashType::Type,
+        "data" => ScriptHashType::Data,
+        other => return Err(format!("invalid hash type: {}", other).into()),
+    };
+    Ok(Script::new_builder()
+        .code_hash(parse_hash(parts[0])?.pack())
+        .hash_type(hash_type.into())
+        .args(Bytes::from(parse_hex(parts[2])?).pack())
+        .build())
+}
+
+fn load_cat(path: &str) -> Result<CatCell> {
+    let file: CellFile = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let output: CellOutput = file.output.into();
+    let data = NFTData::from_slice(file.data.as_bytes())?;
+    Ok(CatCell {
+        input: CellInput::new_builder()
+            .previous_output(file.out_point.into())
+            .build(),
+        lock: output.lock(),
+        capacity: output.capacity().unpack(),
+        data,
+    })
+}
+
+//配置cell必须是type args引用的那个，合约每次执行都会读取它，所以作为cell dep加入交易
+fn load_config(path: &str, type_args: &[u8]) -> Result<(CellDep, Config)> {
+    let file: CellFile = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let output: CellOutput = file.output.into();
+    let expected =
+        &type_args[CONFIG_TYPE_HASH_OFFSET..CONFIG_TYPE_HASH_OFFSET + CONFIG_TYPE_HASH_LEN];
+    match output.type_().to_opt() {
+        Some(type_script) if type_script.calc_script_hash().as_slice() == expected => {}
+        _ => return Err(format!("{} is not the config cell of the type args", path).into()),
+    }
+    let cell_dep = CellDep::new_builder()
+        .out_point(file.out_point.into())
+        .dep_type(DepType::Code.into())
+        .build();
+    Ok((cell_dep, Config::from_slice(file.data.as_bytes())?))
+}
+
+//type args没有引用配置cell时使用默认配置
+fn load_deployment(args: &Args) -> Result<(CatDeployment, Config)> {
+    let type_args = Bytes::from(parse_hex(args.required("type-args")?)?);
+    let mut deployment = deployment::load(
+        Path::new(args.get("deployment").unwrap_or("deployment.toml")),
+        Path::new(args.required("migration")?),
+        type_args.clone(),
+    )?;
+    let has_config = type_args.len() >= CONFIG_TYPE_HASH_OFFSET + CONFIG_TYPE_HASH_LEN;
+    let config = match (args.get("config"), has_config) {
+        (Some(path), true) => {
+            let (cell_dep, config) = load_config(path, &type_args)?;
+            deployment.cell_deps.push(cell_dep);
+            config
+        }
+        (None, false) => Config::default(),
+        (Some(_), false) => return Err("the type args don't reference a config cell".into()),
+        (None, true) => return Err("the type args reference a config cell, pass --config".into()),
+    };
+    Ok((deployment, config))
+}
+
+fn print_cat(label: &str, cat: &NFTData) {
+    println!(
+        "{}: {} hash 0x{} fishes {} season {} wins {} losses {} generation {}",
+        label,
+        cat.name(),
+        faster_hex::hex_string(&cat.hash).expect("hex"),
+        cat.fishes,
+        cat.season,
+        cat.wins,
+        cat.losses,
+        cat.generation
+    );
+}
+
+fn write_tx(args: &Args, built: &BuiltTx) -> Result<()> {
+    let out = args.required("out")?;
+    let tx: json_types::Transaction = built.tx.data().into();
+    fs::write(out, serde_json::to_string_pretty(&tx)?)?;
+    let tx_hash: H256 = built.tx.hash().unpack();
+    println!("tx hash: {:#x}", tx_hash);
+    println!("written to {}", out);
+    Ok(())
+}
+
+fn mint(args: &Args) -> Result<()> {
+    let capacity = match args.get("capacity") {
+        Some(capacity) => capacity.parse()?,
+        None => DEFAULT_CAT_CAPACITY,
+    };
+    let (deployment, _) = load_deployment(args)?;
+    let built = MintBuilder::new(deployment)
+        .owner_input(
+            CellInput::new_builder()
+                .previous_output(parse_out_point(args.required("owner-input")?)?)
+                .build(),
+        )
+        .cat(
+            args.required("name")?,
+            parse_lock(args.required("lock")?)?,
+            capacity,
+        )?
+        .build()?;
+    print_cat("minted", &built.cats[0]);
+    write_tx(args, &built)
+}
+
+fn transfer(args: &Args) -> Result<()> {
+    let cats = args.all("cat");
+    let locks = args.all("lock");
+    if cats.is_empty() || cats.len() != locks.len() {
+        return Err("every --cat needs a --lock".into());
+    }
+    let (deployment, _) = load_deployment(args)?;
+    let mut builder = TransferBuilder::new(deployment);
+    for (cat, lock) in cats.into_iter().zip(locks) {
+        builder = builder.cat(load_cat(cat)?, parse_lock(lock)?);
+    }
+    let built = builder.build()?;
+    for cat in built.cats.iter() {
+        print_cat("transferred", cat);
+    }
+    write_tx(args, &built)
+}
+
+fn fight(args: &Args) -> Result<()> {
+    let cats = args.all("cat");
+    if cats.len() != 2 {
+        return Err("fight needs exactly two --cat".into());
+    }
+    let (deployment, config) = load_deployment(args)?;
+    if config.is_paused() {
+        return Err("fights are paused by the config".into());
+    }
+    let mut builder = FightBuilder::new(deployment, load_cat(cats[0])?, load_cat(cats[1])?)
+        .params(config.params)
+        .season(config.season);
+    if let Some(max_rounds) = args.get("max-rounds") {
+        builder = builder.max_rounds(max_rounds.parse()?);
+    }
+    let (built, result) = builder.build()?;
+    println!("cat {} wins in {} rounds", result.winner, result.rounds);
+    print_cat("cat 0", &built.cats[0]);
+    print_cat("cat 1", &built.cats[1]);
+    write_tx(args, &built)
+}
+
+fn run() -> Result<()> {
+    let args = Args::parse(std::env::args().skip(1))?;
+    match args.command.as_str() {
+        "mint" => mint(&args),
+        "transfer" => transfer(&args),
+        "fight" => fight(&args),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        other => Err(format!("unknown command: {}", other).into()),
+    }
+}
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("error: {}\n\n{}", err, USAGE);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,95 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use serde_json::Value;
+
+//仓库自己的deployment.toml和一份capsule deploy格式的部署结果
+const MIGRATION: &str = "tests/fixtures/migration.json";
+const SOURLY_CAT_TX_HASH: &str =
+    "0x7f7cdbcb6a6a39a2cd4bc1c0b3ebbf85a4bd7bea3a02ef2e9b7ae5c62fee8b1a";
+const SOURLY_CAT_DATA_HASH: &str =
+    "0x2d2ad64c2f0d4e4e8f5fc63a2ef5c4f5a3bd3a0e3c0c1b6b8f0e8a3d4c5b6a79";
+const TYPE_ARGS: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+const OWNER_INPUT: &str = "0x2222222222222222222222222222222222222222222222222222222222222222:0";
+const LOCK: &str = "0x9bd7e06f3ecf4be0f2fcd2188b23f1b9fcc88e5d4b65a8637b17723bbda3cce8:type:0x3333333333333333333333333333333333333333";
+
+fn manifest_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn out_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("sourly-cat-{}-{}.json", std::process::id(), name))
+}
+
+fn mint(deployment: &PathBuf, out: &PathBuf) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_sourly-cat"))
+        .current_dir(manifest_dir())
+        .arg("mint")
+        .arg("--deployment")
+        .arg(deployment)
+        .arg("--migration")
+        .arg(MIGRATION)
+        .arg("--type-args")
+        .arg(TYPE_ARGS)
+        .arg("--owner-input")
+        .arg(OWNER_INPUT)
+        .arg("--name")
+        .arg("Tom")
+        .arg("--lock")
+        .arg(LOCK)
+        .arg("--out")
+        .arg(out)
+        .output()
+        .expect("run sourly-cat")
+}
+
+#[test]
+fn test_mint_with_repo_deployment() {
+    let out = out_path("mint");
+    let output = mint(&manifest_dir().join("../deployment.toml"), &out);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let tx: Value = serde_json::from_str(&fs::read_to_string(&out).expect("read tx")).expect("tx");
+    fs::remove_file(&out).ok();
+
+    //sourly_cat按代码引用，猫的type script使用它的data hash
+    let cell_deps = tx["cell_deps"].as_array().expect("cell deps");
+    assert_eq!(cell_deps.len(), 1);
+    assert_eq!(cell_deps[0]["out_point"]["tx_hash"], SOURLY_CAT_TX_HASH);
+    assert_eq!(cell_deps[0]["out_point"]["index"], "0x0");
+    assert_eq!(cell_deps[0]["dep_type"], "code");
+
+    let inputs = tx["inputs"].as_array().expect("inputs");
+    assert_eq!(inputs.len(), 1);
+    let outputs = tx["outputs"].as_array().expect("outputs");
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(outputs[0]["type"]["code_hash"], SOURLY_CAT_DATA_HASH);
+    assert_eq!(outputs[0]["type"]["hash_type"], "data");
+    assert_eq!(outputs[0]["type"]["args"], TYPE_ARGS);
+    assert_eq!(
+        tx["outputs_data"].as_array().expect("outputs data").len(),
+        1
+    );
+}
+
+#[test]
+fn test_mint_without_sourly_cat_cell() {
+    let deployment = out_path("deployment");
+    fs::write(
+        &deployment,
+        "[[cells]]\nname = \"cat_market\"\nenable_type_id = false\nlocation = { file = \"build/release/cat_market\" }\n",
+    )
+    .expect("write deployment");
+    let out = out_path("missing");
+    let output = mint(&deployment, &out);
+    fs::remove_file(&deployment).ok();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("cell sourly_cat is not in the deployment"));
+    assert!(!out.exists());
+}
@@ -0,0 +1,66 @@
+// Import from `core` instead of from `std` since we are in no-std mode
+use core::result::Result;
+
+// Import heap related library from `alloc`
+// https://doc.rust-lang.org/alloc/index.html
+use alloc::vec::Vec;
+
+// Import CKB syscalls and structures
+// https://nervosnetwork.github.io/ckb-std/riscv64imac-unknown-none-elf/doc/ckb_std/index.html
+use ckb_std::{
+    ckb_constants::Source,
+    ckb_types::{bytes::Bytes, prelude::*},
+    high_level::{load_cell_data, load_cell_lock_hash, load_script, QueryIter},
+};
+
+use sourly_cat_core::item::Item;
+
+use crate::error::Error;
+
+//args: owner lock hash 32 bytes
+const OWNER_LOCK_HASH_LEN: usize = 32;
+
+//data的格式和装备种类与sourly_cat一致，由sourly_cat_core解析
+fn check_item_data(data: &[u8]) -> Result<(), Error> {
+    Item::from_slice(data)?;
+    Ok(())
+}
+
+// Owner具有创建装备的权力
+fn check_owner_mode(args: &Bytes) -> bool {
+    let owner_lock_hash = if args.len() > OWNER_LOCK_HASH_LEN {
+        &args[..OWNER_LOCK_HASH_LEN]
+    } else {
+        &args[..]
+    };
+    QueryIter::new(load_cell_lock_hash, Source::Input)
+        .find(|lock_hash| owner_lock_hash[..] == lock_hash[..])
+        .is_some()
+}
+
+pub fn main() -> Result<(), Error> {
+    let script = load_script()?;
+    let args: Bytes = script.args().unpack();
+
+    let outputs: Vec<Vec<u8>> = QueryIter::new(load_cell_data, Source::GroupOutput).collect();
+    for data in outputs.iter() {
+        check_item_data(data)?;
+    }
+
+    //Owner可以创建装备
+    if check_owner_mode(&args) {
+        return Ok(());
+    }
+
+    //否则每个输出的装备都必须来自一个相同的输入装备，可以转移或者销毁，但不能凭空产生或修改属性
+    let mut inputs: Vec<Vec<u8>> = QueryIter::new(load_cell_data, Source::GroupInput).collect();
+    for data in outputs.iter() {
+        let index = inputs
+            .iter()
+            .position(|input| input == data)
+            .ok_or(Error::ErrItemForged)?;
+        inputs.swap_remove(index);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+// Import from `core` instead of from `std` since we are in no-std mode
+use core::result::Result;
+
+// Import CKB syscalls and structures
+// https://nervosnetwork.github.io/ckb-std/riscv64imac-unknown-none-elf/doc/ckb_std/index.html
+use ckb_std::{
+    ckb_constants::Source,
+    ckb_types::{bytes::Bytes, packed::Script, prelude::*},
+    high_level::{
+        load_cell_capacity, load_cell_lock, load_cell_lock_hash, load_input_since, load_script,
+        QueryIter,
+    },
+};
+
+use crate::error::Error;
+
+//一口价挂单 args: 卖家 lock hash 32 bytes + 价格 8 bytes(单位shannon，大端序)
+const SELLER_LOCK_HASH_LEN: usize = 32;
+const PRICE_LEN: usize = 8;
+const LISTING_ARGS_LEN: usize = SELLER_LOCK_HASH_LEN + PRICE_LEN;
+//荷兰式拍卖 args: 卖家 lock hash 32 bytes + 起拍价 8 bytes + 底价 8 bytes
+// + 开始区块 8 bytes + 结束区块 8 bytes，均为大端序
+const BLOCK_NUMBER_LEN: usize = 8;
+const AUCTION_ARGS_LEN: usize = SELLER_LOCK_HASH_LEN + PRICE_LEN * 2 + BLOCK_NUMBER_LEN * 2;
+
+//since的最高字节是标志位，0表示按绝对区块高度
+const SINCE_FLAGS_MASK: u64 = 0xff00_0000_0000_0000;
+const SINCE_VALUE_MASK: u64 = 0x00ff_ffff_ffff_ffff;
+
+//定价方式
+enum Pricing {
+    Fixed(u64),
+    //价格在开始区块和结束区块之间从起拍价线性下降到底价
+    Dutch {
+        start_price: u64,
+        end_price: u64,
+        start_block: u64,
+        end_block: u64,
+    },
+}
+
+//挂单信息
+struct Listing {
+    seller_lock_hash: [u8; 32],
+    pricing: Pricing,
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_be_bytes(buf)
+}
+
+impl Listing {
+    fn from_args(args: &[u8]) -> Result<Self, Error> {
+        let pricing = match args.len() {
+            LISTING_ARGS_LEN => Pricing::Fixed(read_u64(args, SELLER_LOCK_HASH_LEN)),
+            AUCTION_ARGS_LEN => {
+                let start_price = read_u64(args, SELLER_LOCK_HASH_LEN);
+                let end_price = read_u64(args, SELLER_LOCK_HASH_LEN + PRICE_LEN);
+                let start_block = read_u64(args, SELLER_LOCK_HASH_LEN + PRICE_LEN * 2);
+                let end_block = read_u64(
+                    args,
+                    SELLER_LOCK_HASH_LEN + PRICE_LEN * 2 + BLOCK_NUMBER_LEN,
+                );
+                //价格只能下降，且拍卖至少持续一个区块
+                if start_price < end_price || start_block >= end_block {
+                    return Err(Error::Encoding);
+                }
+                Pricing::Dutch {
+                    start_price,
+                    end_price,
+                    start_block,
+                    end_block,
+                }
+            }
+            _ => return Err(Error::Encoding),
+        };
+        let mut seller_lock_hash = [0u8; 32];
+        seller_lock_hash.copy_from_slice(&args[..SELLER_LOCK_HASH_LEN]);
+        Ok(Listing {
+            seller_lock_hash,
+            pricing,
+        })
+    }
+
+    //第index个输入的成交价格，拍卖按输入的since所指定的区块高度计算
+    fn price(&self, index: usize) -> Result<u64, Error> {
+        match self.pricing {
+            Pricing::Fixed(price) => Ok(price),
+            Pricing::Dutch {
+                start_price,
+                end_price,
+                start_block,
+                end_block,
+            } => {
+                let since = load_input_since(index, Source::Input)?;
+                if since & SINCE_FLAGS_MASK != 0 {
+                    return Err(Error::ErrInvalidSince);
+                }
+                let block = since & SINCE_VALUE_MASK;
+                if block <= start_block {
+                    return Ok(start_price);
+                }
+                if block >= end_block {
+                    return Ok(end_price);
+                }
+                let decayed = (start_price - end_price) as u128 * (block - start_block) as u128
+                    / (end_block - start_block) as u128;
+                Ok(start_price - decayed as u64)
+            }
+        }
+    }
+}
+
+//判断是否是同一个合约的lock
+fn is_market_lock(script: &Script, lock: &Script) -> bool {
+    lock.code_hash().as_slice() == script.code_hash().as_slice()
+        && lock.hash_type().as_slice() == script.hash_type().as_slice()
+}
+
+//交易中同一个卖家所有挂单的价格之和，防止一笔付款同时买走多只猫
+fn collect_seller_price(script: &Script, seller_lock_hash: &[u8; 32]) -> Result<u64, Error> {
+    let mut total: u64 = 0;
+    for (index, lock) in QueryIter::new(load_cell_lock, Source::Input).enumerate() {
+        if !is_market_lock(script, &lock) {
+            continue;
+        }
+        let args: Bytes = lock.args().unpack();
+        let listing = Listing::from_args(&args)?;
+        if listing.seller_lock_hash != *seller_lock_hash {
+            continue;
+        }
+        total = total
+            .checked_add(listing.price(index)?)
+            .ok_or(Error::ErrOverflow)?;
+    }
+    Ok(total)
+}
+
+//交易中付给卖家的capacity
+fn collect_seller_payment(seller_lock_hash: &[u8; 32]) -> Result<u64, Error> {
+    let mut paid: u64 = 0;
+    for (lock_hash, capacity) in QueryIter::new(load_cell_lock_hash, Source::Output)
+        .zip(QueryIter::new(load_cell_capacity, Source::Output))
+    {
+        if lock_hash != *seller_lock_hash {
+            continue;
+        }
+        paid = paid.checked_add(capacity).ok_or(Error::ErrOverflow)?;
+    }
+    Ok(paid)
+}
+
+pub fn main() -> Result<(), Error> {
+    let script = load_script()?;
+    let args: Bytes = script.args().unpack();
+    let listing = Listing::from_args(&args)?;
+
+    //卖家撤单：交易中有卖家lock的输入
+    let is_seller = QueryIter::new(load_cell_lock_hash, Source::Input)
+        .find(|lock_hash| *lock_hash == listing.seller_lock_hash)
+        .is_some();
+    if is_seller {
+        return Ok(());
+    }
+
+    //买家购买：付给卖家的capacity不能少于挂单价格，拍卖的价格由since决定
+    let price = collect_seller_price(&script, &listing.seller_lock_hash)?;
+    let paid = collect_seller_payment(&listing.seller_lock_hash)?;
+    if paid < price {
+        return Err(Error::ErrInsufficientPayment);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,174 @@
+// Import from `core` instead of from `std` since we are in no-std mode
+use core::result::Result;
+
+// Import heap related library from `alloc`
+// https://doc.rust-lang.org/alloc/index.html
+use alloc::vec::Vec;
+
+// Import CKB syscalls and structures
+// https://nervosnetwork.github.io/ckb-std/riscv64imac-unknown-none-elf/doc/ckb_std/index.html
+use ckb_std::{
+    ckb_constants::Source,
+    ckb_types::{bytes::Bytes, prelude::*},
+    high_level::{
+        load_cell_lock_hash, load_cell_type_hash, load_input_since, load_script, load_script_hash,
+        load_witness_args, QueryIter,
+    },
+};
+
+use crate::error::Error;
+
+//args: 主人 lock hash 32 bytes + 借用者 lock hash 32 bytes + 到期区块 8 bytes(大端序)
+const LOCK_HASH_LEN: usize = 32;
+const BLOCK_NUMBER_LEN: usize = 8;
+const RENTAL_ARGS_LEN: usize = LOCK_HASH_LEN * 2 + BLOCK_NUMBER_LEN;
+
+//since的最高字节是标志位，0表示按绝对区块高度
+const SINCE_FLAGS_MASK: u64 = 0xff00_0000_0000_0000;
+const SINCE_VALUE_MASK: u64 = 0x00ff_ffff_ffff_ffff;
+
+//sourly_cat的战斗：双方的猫是输入0，1，战斗轮次在输入1的见证的input_type中
+const FIGHT_INPUTS: usize = 2;
+
+//租借信息
+struct Rental {
+    owner_lock_hash: [u8; 32],
+    borrower_lock_hash: [u8; 32],
+    end_block: u64,
+}
+
+impl Rental {
+    fn from_args(args: &[u8]) -> Result<Self, Error> {
+        if args.len() != RENTAL_ARGS_LEN {
+            return Err(Error::Encoding);
+        }
+        let mut owner_lock_hash = [0u8; 32];
+        owner_lock_hash.copy_from_slice(&args[..LOCK_HASH_LEN]);
+        let mut borrower_lock_hash = [0u8; 32];
+        borrower_lock_hash.copy_from_slice(&args[LOCK_HASH_LEN..LOCK_HASH_LEN * 2]);
+        let mut end_block = [0u8; BLOCK_NUMBER_LEN];
+        end_block.copy_from_slice(&args[LOCK_HASH_LEN * 2..RENTAL_ARGS_LEN]);
+        Ok(Rental {
+            owner_lock_hash,
+            borrower_lock_hash,
+            end_block: u64::from_be_bytes(end_block),
+        })
+    }
+}
+
+//交易中是否有该lock hash的输入，即对应的人签了名
+fn has_input_lock(lock_hash: &[u8; 32]) -> bool {
+    QueryIter::new(load_cell_lock_hash, Source::Input)
+        .find(|hash| hash == lock_hash)
+        .is_some()
+}
+
+//租期结束后主人才能收回，每个输入的since都必须不早于到期区块
+fn check_reclaim(rental: &Rental) -> Result<(), Error> {
+    for since in QueryIter::new(load_input_since, Source::GroupInput) {
+        if since & SINCE_FLAGS_MASK != 0 {
+            return Err(Error::ErrInvalidSince);
+        }
+        if since & SINCE_VALUE_MASK < rental.end_block {
+            return Err(Error::ErrRentalNotExpired);
+        }
+    }
+    Ok(())
+}
+
+//借用者只能在租期内使用：每个输入都要有绝对区块高度的since，并且早于到期区块。
+//since只限制交易最早上链的区块，到期后由主人收回来结束租借
+fn check_borrow_since(rental: &Rental) -> Result<(), Error> {
+    for since in QueryIter::new(load_input_since, Source::GroupInput) {
+        if since == 0 || since & SINCE_FLAGS_MASK != 0 {
+            return Err(Error::ErrInvalidSince);
+        }
+        if since & SINCE_VALUE_MASK >= rental.end_block {
+            return Err(Error::ErrRentalExpired);
+        }
+    }
+    Ok(())
+}
+
+//交易中这种猫的cell数量
+fn count_cats(cat_type_hash: &[u8; 32], source: Source) -> usize {
+    QueryIter::new(load_cell_type_hash, source)
+        .filter(|type_hash| type_hash.as_ref() == Some(cat_type_hash))
+        .count()
+}
+
+//是否是sourly_cat的战斗：输入0，1是同一种猫，输入1的见证中有不为0的战斗轮次。
+//输入和输出中都只能有这两只猫，否则sourly_cat会按转账和改名来检查，
+//借用者的签名就可能被当作主人的签名
+fn is_fight(cat_type_hash: &[u8; 32]) -> Result<bool, Error> {
+    for i in 0..FIGHT_INPUTS {
+        if load_cell_type_hash(i, Source::Input)? != Some(*cat_type_hash) {
+            return Ok(false);
+        }
+    }
+    if count_cats(cat_type_hash, Source::Input) != FIGHT_INPUTS
+        || count_cats(cat_type_hash, Source::Output) != FIGHT_INPUTS
+    {
+        return Ok(false);
+    }
+    let rounds: Vec<u8> = match load_witness_args(1, Source::Input) {
+        Ok(witness) => match witness.input_type().to_opt() {
+            Some(rounds) => rounds.unpack(),
+            None => return Ok(false),
+        },
+        Err(_) => return Ok(false),
+    };
+    Ok(rounds.len() >= 2 && u16::from_be_bytes([rounds[0], rounds[1]]) > 0)
+}
+
+//借用者只能拿猫去战斗，战斗结束后每只猫必须回到相同位置的输出，
+//type不变，lock仍然是同一个租借lock(args完全相同)
+fn check_borrow(rental: &Rental) -> Result<(), Error> {
+    check_borrow_since(rental)?;
+
+    let script_hash = load_script_hash()?;
+    let rented = QueryIter::new(load_cell_lock_hash, Source::Input)
+        .enumerate()
+        .filter(|(_, lock_hash)| *lock_hash == script_hash)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    for i in rented {
+        //租来的猫只能是战斗的一方
+        if i >= FIGHT_INPUTS {
+            return Err(Error::ErrNotFight);
+        }
+        let cat_type_hash = load_cell_type_hash(i, Source::Input)?.ok_or(Error::ErrNotFight)?;
+        if !is_fight(&cat_type_hash)? {
+            return Err(Error::ErrNotFight);
+        }
+        let returned = match load_cell_lock_hash(i, Source::Output) {
+            Ok(lock_hash) => {
+                lock_hash == script_hash
+                    && load_cell_type_hash(i, Source::Output)? == Some(cat_type_hash)
+            }
+            Err(_) => false,
+        };
+        if !returned {
+            return Err(Error::ErrCatNotReturned);
+        }
+    }
+    Ok(())
+}
+
+pub fn main() -> Result<(), Error> {
+    let script = load_script()?;
+    let args: Bytes = script.args().unpack();
+    let rental = Rental::from_args(&args)?;
+
+    //主人收回
+    if has_input_lock(&rental.owner_lock_hash) {
+        return check_reclaim(&rental);
+    }
+
+    //借用者使用，租期内由借用者签名即可，只能用来战斗
+    if has_input_lock(&rental.borrower_lock_hash) {
+        return check_borrow(&rental);
+    }
+
+    Err(Error::ErrUnauthorized)
+}
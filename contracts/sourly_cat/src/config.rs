@@ -0,0 +1,83 @@
+// Import heap related library from `alloc`
+// https://doc.rust-lang.org/alloc/index.html
+use alloc::vec::Vec;
+
+use ckb_std::{
+    ckb_constants::Source,
+    high_level::{load_cell_data, load_cell_lock_hash, load_cell_type_hash, QueryIter},
+};
+
+use sourly_cat_core::battle::Params;
+use sourly_cat_core::config::{Config as ConfigData, Revive, Season};
+
+use crate::error::Error;
+
+//配置cell，通过cell dep引用，用type hash(一般是type id)定位，
+//管理员可以更新它的内容而不用重新部署合约，data的格式见sourly_cat_core::config
+pub struct Config {
+    data: ConfigData,
+    //配置cell的lock hash，即管理员收取费用的lock
+    lock_hash: [u8; 32],
+}
+
+impl Config {
+    //从cell dep中找到type hash对应的配置cell
+    pub fn load(type_hash: &[u8]) -> Result<Self, Error> {
+        let index = QueryIter::new(load_cell_type_hash, Source::CellDep)
+            .position(|hash| match hash {
+                Some(hash) => hash[..] == type_hash[..],
+                None => false,
+            })
+            .ok_or(Error::ErrConfigMissing)?;
+        let data = ConfigData::from_slice(&load_cell_data(index, Source::CellDep)?)?;
+        let lock_hash = load_cell_lock_hash(index, Source::CellDep)?;
+        Ok(Config { data, lock_hash })
+    }
+
+    //交易的输入中出现的不同管理员数量达到阈值，即视为Owner
+    pub fn is_admin_mode(&self) -> bool {
+        let input_lock_hashes: Vec<[u8; 32]> =
+            QueryIter::new(load_cell_lock_hash, Source::Input).collect();
+        let admin_lock_hashes = &self.data.admin_lock_hashes;
+        let signed = admin_lock_hashes
+            .iter()
+            .enumerate()
+            .filter(|(i, admin)| {
+                //同一个管理员重复配置只算一次
+                !admin_lock_hashes[..*i].contains(admin) && input_lock_hashes.contains(admin)
+            })
+            .count();
+        signed >= self.data.threshold as usize
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.data.is_paused()
+    }
+
+    pub fn params(&self) -> Params {
+        self.data.params.clone()
+    }
+
+    pub fn season(&self) -> Season {
+        self.data.season.clone()
+    }
+
+    pub fn revive(&self) -> Revive {
+        self.data.revive.clone()
+    }
+
+    //没有配置时不使用装备
+    pub fn item_type_hash(&self) -> Option<[u8; 32]> {
+        self.data.item_type_hash
+    }
+
+    //复活费用付给配置cell的lock
+    pub fn lock_hash(&self) -> [u8; 32] {
+        self.lock_hash
+    }
+
+    //租借lock不检查见证，这样的猫不能由主人签名的操作修改
+    pub fn rental_code_hash(&self) -> Option<[u8; 32]> {
+        self.data.rental_code_hash
+    }
+}
@@ -9,97 +9,305 @@ use alloc::vec::Vec;
 // https://nervosnetwork.github.io/ckb-std/riscv64imac-unknown-none-elf/doc/ckb_std/index.html
 use ckb_std::{
     ckb_constants::Source,
-    ckb_types::{bytes::Bytes, prelude::*},
-    high_level::{load_cell_data, load_cell_lock_hash, load_script, load_witness_args, QueryIter},
+    ckb_types::{bytes::Bytes, packed::WitnessArgs, prelude::*},
+    high_level::{
+        load_cell_capacity, load_cell_data, load_cell_lock, load_cell_lock_hash,
+        load_cell_type_hash, load_script, load_script_hash, load_witness_args, QueryIter,
+    },
 };
 
-use super::hash;
-use crate::error::Error;
-
-//hash 20 bytes + fishes 4 bytes + name (min 2bytes)
-const MIN_NFT_DATA_LEN: usize = 40;
-#[derive(PartialEq, Clone, Debug)]
-pub struct NFTData {
-    name: [u8; 16],
-    hash: [u8; 20],
-    fishes: i32,
-}
+use sourly_cat_core::battle::{settle, Params, Statistics};
+use sourly_cat_core::config::{Revive, Season};
+use sourly_cat_core::model::{is_extensions_update_allowed, is_valid_name, Extension, NFTData};
 
-impl From<&[u8]> for NFTData {
-    fn from(data: &[u8]) -> Self {
-        let mut name = [0u8; 16];
-        name.copy_from_slice(&data[0..16]);
-        let mut hash = [0u8; 20];
-        hash.copy_from_slice(&data[16..36]);
-        let mut fishes_byte = [0u8; 4];
-        fishes_byte.copy_from_slice(&data[36..40]);
-        //采用大端序
-        let fishes = i32::from_be_bytes(fishes_byte);
-        NFTData {
-            name: name,
-            hash: hash,
-            fishes: fishes,
-        }
+use crate::config::Config;
+use crate::error::Error;
+use crate::item::{check_potions_burned, load_equipment};
+
+//args: owner lock hash 32 bytes + 改名费 4 bytes(可选，大端序)
+// + 配置cell的type hash 32 bytes(可选)
+const OWNER_LOCK_HASH_LEN: usize = 32;
+const RENAME_FEE_LEN: usize = 4;
+const CONFIG_TYPE_HASH_LEN: usize = 32;
+//args中没有配置改名费时的默认值
+const DEFAULT_RENAME_FEE: i32 = 10;
+
+//args的前32字节是Owner的lock hash
+fn owner_lock_hash(args: &Bytes) -> &[u8] {
+    if args.len() > OWNER_LOCK_HASH_LEN {
+        &args[..OWNER_LOCK_HASH_LEN]
+    } else {
+        &args[..]
     }
 }
 
-#[derive(Debug)]
-pub struct Statistics {
-    hp: u8,
-    atk: u8,
-    def: u8,
-    lck: u8,
+//改名需要扣除的fish数目，紧跟在owner lock hash之后
+fn rename_fee(args: &Bytes) -> Result<i32, Error> {
+    if args.len() < OWNER_LOCK_HASH_LEN + RENAME_FEE_LEN {
+        return Ok(DEFAULT_RENAME_FEE);
+    }
+    let mut fee_bytes = [0u8; RENAME_FEE_LEN];
+    fee_bytes.copy_from_slice(&args[OWNER_LOCK_HASH_LEN..OWNER_LOCK_HASH_LEN + RENAME_FEE_LEN]);
+    let fee = i32::from_be_bytes(fee_bytes);
+    if fee < 0 {
+        return Err(Error::Encoding);
+    }
+    Ok(fee)
 }
 
-impl From<[u8; 20]> for Statistics {
-    fn from(hash: [u8; 20]) -> Self {
-        //只看每五个字节的最后一个字节
-        let hp = hash[4] % 100 + 1;
-        let atk = hash[9] % 100 + 1;
-        let def = hash[14] % 100 + 1;
-        let lck = hash[19] % 100 + 1;
-        return Statistics { hp, atk, def, lck };
+//配置cell的type hash，紧跟在改名费之后
+fn config_type_hash(args: &Bytes) -> Option<&[u8]> {
+    let start = OWNER_LOCK_HASH_LEN + RENAME_FEE_LEN;
+    if args.len() < start + CONFIG_TYPE_HASH_LEN {
+        return None;
     }
+    Some(&args[start..start + CONFIG_TYPE_HASH_LEN])
 }
 
 // Owner具有创建NFT的权力
-fn check_owner_mode(args: &Bytes) -> Result<bool, Error> {
+fn check_owner_mode(args: &Bytes, config: &Option<Config>) -> Result<bool, Error> {
+    //配置了管理员时，由管理员代替args中的Owner，这样可以更换管理员
+    if let Some(config) = config {
+        return Ok(config.is_admin_mode());
+    }
+
     // With owner lock script extracted, we will look through each input in the
     // current transaction to see if any unlocked cell uses owner lock.
+    let owner_lock_hash = owner_lock_hash(args);
     let is_owner_mode = QueryIter::new(load_cell_lock_hash, Source::Input)
-        .find(|lock_hash| args[..] == lock_hash[..])
+        .find(|lock_hash| owner_lock_hash[..] == lock_hash[..])
         .is_some();
     Ok(is_owner_mode)
 }
 
+//名字必须是可打印的UTF-8字符串，不足16字节的部分用0补齐
+fn check_name(name: &[u8; 16]) -> Result<(), Error> {
+    if !is_valid_name(name) {
+        return Err(Error::ErrInvalidName);
+    }
+    Ok(())
+}
+
+//改名：只允许修改名字并扣除改名费，hash等其他字段保持不变
+fn check_rename(
+    index: usize,
+    input: &NFTData,
+    output: &NFTData,
+    fee: i32,
+    rental_code_hash: &Option<[u8; 32]>,
+) -> Result<(), Error> {
+    if input.hash != output.hash {
+        return Err(Error::ErrWrongTransfer);
+    }
+    check_name(&output.name)?;
+
+    //fish不够支付改名费
+    if input.fishes < fee {
+        return Err(Error::ErrWrongRename);
+    }
+    let expected = NFTData {
+        name: output.name,
+        fishes: input.fishes - fee,
+        extensions: output.extensions.clone(),
+        ..input.clone()
+    };
+    if *output != expected {
+        return Err(Error::ErrWrongRename);
+    }
+    //改名时可以同时修改图片和描述
+    check_extensions_update(&input.extensions, &output.extensions)?;
+
+    //改名不能同时转移归属权，并且必须由主人签名
+    if !check_owner_signed(index, rental_code_hash)? {
+        return Err(Error::ErrWrongRename);
+    }
+    Ok(())
+}
+
+//复活：fish为负数的猫恢复到配置的fish数目，其他字段保持不变，费用在交易最后统一检查
+fn check_revive(
+    index: usize,
+    input: &NFTData,
+    output: &NFTData,
+    revive: &Revive,
+    rental_code_hash: &Option<[u8; 32]>,
+) -> Result<(), Error> {
+    let expected = NFTData {
+        fishes: revive.fishes,
+        ..input.clone()
+    };
+    if *output != expected {
+        return Err(Error::ErrWrongRevive);
+    }
+    if !check_owner_signed(index, rental_code_hash)? {
+        return Err(Error::ErrWrongRevive);
+    }
+    Ok(())
+}
+
+//修改扩展记录：只改了扩展记录，并且由主人签名
+fn check_update(
+    index: usize,
+    input: &NFTData,
+    output: &NFTData,
+    rental_code_hash: &Option<[u8; 32]>,
+) -> Result<(), Error> {
+    check_extensions_update(&input.extensions, &output.extensions)?;
+    if !check_owner_signed(index, rental_code_hash)? {
+        return Err(Error::ErrWrongExtension);
+    }
+    Ok(())
+}
+
+//只允许增加，删除或修改可变的记录
+fn check_extensions_update(input: &[Extension], output: &[Extension]) -> Result<(), Error> {
+    if !is_extensions_update_allowed(input, output) {
+        return Err(Error::ErrWrongExtension);
+    }
+    Ok(())
+}
+
+//除了扩展记录以外都相同
+fn is_extensions_update(input: &NFTData, output: &NFTData) -> bool {
+    let expected = NFTData {
+        extensions: output.extensions.clone(),
+        ..input.clone()
+    };
+    *output == expected
+}
+
+//猫的归属权不变，并且由主人签名：同一个lock的第一个输入的见证中lock字段不能为空，
+//否则anyone-can-pay lock不需要签名也能解锁；租借lock不检查见证，借用者不能代替主人签名
+fn check_owner_signed(index: usize, rental_code_hash: &Option<[u8; 32]>) -> Result<bool, Error> {
+    if let Some(rental_code_hash) = rental_code_hash {
+        let lock = load_cell_lock(index, Source::GroupInput)?;
+        if lock.code_hash().as_slice() == &rental_code_hash[..] {
+            return Ok(false);
+        }
+    }
+    let lock_hash = load_cell_lock_hash(index, Source::GroupInput)?;
+    if lock_hash != load_cell_lock_hash(index, Source::GroupOutput)? {
+        return Ok(false);
+    }
+    let first_index = match QueryIter::new(load_cell_lock_hash, Source::Input)
+        .position(|hash| hash == lock_hash)
+    {
+        Some(first_index) => first_index,
+        None => return Ok(false),
+    };
+    let witness = load_witness_args(first_index, Source::Input)?;
+    let signature: Vec<u8> = match witness.lock().to_opt() {
+        Some(signature) => signature.unpack(),
+        None => return Ok(false),
+    };
+    Ok(!signature.is_empty())
+}
+
+//复活费用必须支付给fee_lock_hash
+fn check_revive_fee(fee_lock_hash: &[u8], revive: &Revive, count: u64) -> Result<(), Error> {
+    let paid = QueryIter::new(load_cell_lock_hash, Source::Output)
+        .zip(QueryIter::new(load_cell_capacity, Source::Output))
+        .filter(|(lock_hash, _)| lock_hash[..] == fee_lock_hash[..])
+        .try_fold(0u64, |sum, (_, capacity)| sum.checked_add(capacity))
+        .ok_or(Error::ErrWrongRevive)?;
+    let fee = revive.fee.checked_mul(count).ok_or(Error::ErrWrongRevive)?;
+    if paid < fee {
+        return Err(Error::ErrWrongRevive);
+    }
+    Ok(())
+}
+
+fn parse_nft_data(data: Vec<u8>) -> Result<NFTData, Error> {
+    Ok(NFTData::from_slice(&data)?)
+}
+
 //将所有的输入NFT收集起来
 fn collect_inputs_data() -> Result<Vec<NFTData>, Error> {
-    let udt_list: Vec<NFTData> = QueryIter::new(load_cell_data, Source::GroupInput)
-        .map(|data| {
-            if data.len() == MIN_NFT_DATA_LEN {
-                // u128 is 16 bytes
-                Ok(NFTData::from(&data[..]))
-            } else {
-                Err(Error::Encoding)
-            }
-        })
-        .collect::<Result<Vec<_>, Error>>()?;
-    Ok(udt_list)
+    QueryIter::new(load_cell_data, Source::GroupInput)
+        .map(parse_nft_data)
+        .collect()
 }
 
 //将所有的输出NFT收集起来
 fn collect_outputs_data() -> Result<Vec<NFTData>, Error> {
-    let udt_list: Vec<NFTData> = QueryIter::new(load_cell_data, Source::GroupOutput)
-        .map(|data| {
-            if data.len() >= MIN_NFT_DATA_LEN {
-                // u128 is 16 bytes
-                Ok(NFTData::from(&data[..]))
-            } else {
-                Err(Error::Encoding)
-            }
-        })
-        .collect::<Result<Vec<_>, Error>>()?;
-    Ok(udt_list)
+    QueryIter::new(load_cell_data, Source::GroupOutput)
+        .map(parse_nft_data)
+        .collect()
+}
+
+//战斗轮次放在第二个输入的见证的input_type中，前2个字节(大端序)，没有时为0
+fn fight_rounds(wit_args: &WitnessArgs) -> u16 {
+    if let Some(in_type) = wit_args.input_type().to_opt() {
+        let in_type: Vec<u8> = in_type.unpack();
+        if in_type.len() >= 2 {
+            let mut number = [0u8; 2];
+            number[0] = in_type[0];
+            number[1] = in_type[1];
+            return u16::from_be_bytes(number);
+        }
+    }
+    0
+}
+
+//验证战斗结果，不读取交易，双方的属性已经加上了装备
+fn check_fight(
+    n: u16,
+    params: &Params,
+    stats: &[Statistics; 2],
+    input_nft: &[NFTData],
+    output_nft: &[NFTData],
+    lock_hashes: &[[u8; 32]; 2],
+) -> Result<(), Error> {
+    //计算攻击伤害
+    let hurts = [
+        params.hurt(&stats[0], &stats[1])?,
+        params.hurt(&stats[1], &stats[0])?,
+    ];
+
+    //传入任意 n 值，第n回合恰好分出胜负才能确认战斗结果，否则报错
+    let winner = params
+        .round_winner(n, &hurts, stats)
+        .ok_or(Error::ErrWrongResult)?;
+
+    //fish、hash、战绩和世代都必须与规则计算的结果一致，其他字段不变
+    let inputs = [input_nft[0].clone(), input_nft[1].clone()];
+    let expected = settle(
+        &inputs,
+        winner,
+        [&lock_hashes[0], &lock_hashes[1]],
+        stats,
+        params,
+    )
+    .map_err(|_| Error::ErrWrongResult)?;
+    if output_nft[..] != expected[..] {
+        return Err(Error::ErrWrongResult);
+    }
+    Ok(())
+}
+
+//fuzz target调用的函数，只在cargo fuzz编译(--cfg fuzzing)时公开
+#[cfg(fuzzing)]
+pub mod fuzzing {
+    use super::*;
+
+    pub fn parse_nft_data(data: Vec<u8>) -> Result<NFTData, Error> {
+        super::parse_nft_data(data)
+    }
+
+    pub fn fight_rounds(wit_args: &WitnessArgs) -> u16 {
+        super::fight_rounds(wit_args)
+    }
+
+    pub fn check_fight(
+        n: u16,
+        params: &Params,
+        stats: &[Statistics; 2],
+        input_nft: &[NFTData],
+        output_nft: &[NFTData],
+        lock_hashes: &[[u8; 32]; 2],
+    ) -> Result<(), Error> {
+        super::check_fight(n, params, stats, input_nft, output_nft, lock_hashes)
+    }
 }
 
 pub fn main() -> Result<(), Error> {
@@ -111,12 +319,39 @@ pub fn main() -> Result<(), Error> {
     // //Collect output NFT first
     let output_nft = collect_outputs_data()?;
 
+    //读取配置cell
+    let config = match config_type_hash(&args) {
+        Some(type_hash) => Some(Config::load(type_hash)?),
+        None => None,
+    };
+
     //Onwer发起的，Create NFT,
-    if check_owner_mode(&args)? {
+    if check_owner_mode(&args, &config)? {
         //对Owner的操作不做检查
         return Ok(());
     }
 
+    //紧急暂停时只允许普通转账
+    let paused = match &config {
+        Some(config) => config.is_paused(),
+        None => false,
+    };
+    //游戏参数，没有配置时使用默认值
+    let params = match &config {
+        Some(config) => config.params(),
+        None => Params::default(),
+    };
+    let season = match &config {
+        Some(config) => config.season(),
+        None => Season::default(),
+    };
+    let revive = match &config {
+        Some(config) => config.revive(),
+        None => Revive::default(),
+    };
+    let item_type_hash = config.as_ref().and_then(|config| config.item_type_hash());
+    let rental_code_hash = config.as_ref().and_then(|config| config.rental_code_hash());
+
     //收集所有的输入NFT
     let input_nft = collect_inputs_data()?;
 
@@ -127,39 +362,53 @@ pub fn main() -> Result<(), Error> {
 
     if input_nft.len() == 2 {
         //从Witness中读取战斗轮次
-        let mut n = 0;
-        let res = load_witness_args(1, Source::GroupInput);
-
-        if let Ok(wit_args) = res {
-            if let Some(in_type) = wit_args.input_type().to_opt() {
-                let in_type: Vec<u8> = in_type.unpack();
-                if in_type.len() >= 2 {
-                    let mut number = [0u8; 2];
-                    number[0] = in_type[0];
-                    number[1] = in_type[1];
-                    n = u16::from_be_bytes(number);
-                }
-            }
-        }
+        let n = match load_witness_args(1, Source::GroupInput) {
+            Ok(wit_args) => fight_rounds(&wit_args),
+            Err(_) => 0,
+        };
 
         //Fighting
         if n > 0 {
-            //战斗的时候，只允许有两个输入Cell即 0，1
-            let res = load_cell_data(2, Source::Input);
-            if !res.is_err() {
-                return Err(Error::ErrWrongInputOutPut);
+            if paused {
+                return Err(Error::ErrPaused);
+            }
+
+            //战斗的时候，双方的猫必须是输入Cell 0，1；
+            //后面可以附带其他输入，例如借用者用来授权租借lock的cell
+            let script_hash = load_script_hash()?;
+            for i in 0..2 {
+                if load_cell_type_hash(i, Source::Input)? != Some(script_hash) {
+                    return Err(Error::ErrWrongInputOutPut);
+                }
+            }
+
+            //新赛季的第一场战斗先迁移双方的猫，迁移后的赛季和存档必须写进输出，
+            //扩展记录不能变
+            let input_nft = input_nft
+                .iter()
+                .map(|nft| nft.migrate(&season).map_err(Error::from))
+                .collect::<Result<Vec<_>, Error>>()?;
+            for (input, output) in input_nft.iter().zip(output_nft.iter()) {
+                if input.season != output.season
+                    || input.last_season_fishes != output.last_season_fishes
+                {
+                    return Err(Error::ErrWrongResult);
+                }
+                if input.extensions != output.extensions {
+                    return Err(Error::ErrWrongExtension);
+                }
             }
 
             //其中一方不能再战斗了
             if input_nft[0].fishes < 0 || input_nft[1].fishes < 0 {
-                return Err(Error::ErrUnmatchPlayer);
+                return Err(Error::ErrKnockedOut);
             }
             //名字不能变
             if input_nft[0].name != output_nft[0].name || input_nft[1].name != output_nft[1].name {
                 return Err(Error::ErrWrongResult);
             }
 
-            //要求挑战结束后，归属权不变
+            //要求挑战结束后，归属权不变（租借的猫仍然留在同一个租借lock下）
             let args_input1 = load_cell_lock_hash(0, Source::GroupInput)?;
             let args_input2 = load_cell_lock_hash(1, Source::GroupInput)?;
             let args_output1 = load_cell_lock_hash(0, Source::GroupOutput)?;
@@ -171,123 +420,68 @@ pub fn main() -> Result<(), Error> {
             }
 
             //计算双方的挑战前属性值
-            let stats_0: Statistics = (input_nft[0].hash).into();
-            let stats_1: Statistics = (input_nft[1].hash).into();
-            //debug!("stats_0:{:?},stats_1:{:?}", stats_0, stats_1);
-
-            //计算攻击伤害
-            // Hurt1 = ATK1 - ATK1*DEF2/(DEF2 - LCK2*2 + 250) )
-            let hurt_0 = stats_0.atk as u16
-                - stats_0.atk as u16 * stats_1.def as u16
-                    / (250 - stats_1.lck as u16 * 2 + stats_1.def as u16);
-
-            // Hurt2 = ATK2 - ATK2*DEF1/(DEF1 - LCK1*2 + 250)
-            let hurt_1 = stats_1.atk as u16
-                - stats_1.atk as u16 * stats_0.def as u16
-                    / (250 - stats_0.lck as u16 * 2 + stats_0.def as u16);
-
-            //debug!("hurt_0:{},hurt_1:{}", hurt_0, hurt_1);
-
-            //开始回合制攻击
-
-            //0 noone win;1, 1 win;2, 2 win;3, all failed
-            let mut who_win = 0;
-
-            // 传入任意 n 值，满足下列两个条件之一，则可以确认战斗结果
-            //n * Hurt1 > 10 * HP2 且 (n-1) * Hurt2 < 10 * HP1 则 <被挑战者> 胜利
-            //n * Hurt1 < 10 * HP2 且 n * Hurt2 > 10 * HP1 则 <挑战者> 胜利
-            if (n * hurt_0 >= 5 * stats_1.hp as u16) && ((n - 1) * (hurt_1) < 5 * stats_0.hp as u16)
-            {
-                who_win += 1;
-            }
-
-            //验证挑战结果
-            if (n * hurt_0 < 5 * stats_1.hp as u16) && (n * (hurt_1) >= 5 * stats_0.hp as u16) {
-                who_win += 2;
-            }
-
-            if who_win == 1 {
-                //1 Win!
-                //计算输的一方有多少fish，暂时没考虑四舍五入
-                let mut loser_fishes = input_nft[1].fishes - stats_0.atk as i32 / 10;
-
-                //触发隐藏奖励
-                if loser_fishes == 0 {
-                    loser_fishes = 999
+            let mut stats_0: Statistics = (input_nft[0].hash).into();
+            let mut stats_1: Statistics = (input_nft[1].hash).into();
+
+            //加上和猫属于同一个lock的装备和药水，喝掉的药水必须销毁
+            if let Some(item_type_hash) = item_type_hash {
+                let [items_0, items_1] =
+                    load_equipment(&item_type_hash, &[args_input1, args_input2])?;
+                for equipment in items_0.iter() {
+                    stats_0.equip(&equipment.item.bonus);
                 }
-
-                //计算赢的一方的Fish数目
-                let winner_fishes = { input_nft[0].fishes + (stats_1.hp as i32 / 10) };
-
-                // debug!(
-                //     "0 Win, loser_fishes:{}, winner_fishes:{}",
-                //     loser_fishes, winner_fishes
-                // );
-                //检查fish是否对应
-                if (output_nft[0].fishes != winner_fishes) || (output_nft[1].fishes != loser_fishes)
-                {
-                    return Err(Error::ErrWrongResult);
-                }
-
-                //输的一方要更改Hash, blake160(hash+lock_args)
-                let lock_args = load_cell_lock_hash(0, Source::GroupInput)?;
-                let mut conc = Vec::with_capacity(20 + lock_args.len());
-                conc.extend(input_nft[1].hash.iter());
-                conc.extend(lock_args.iter());
-                let res = hash::blake2b_160(conc);
-
-                //检验Hash是否相等，赢的一方不变
-                if !res.eq(&output_nft[1].hash) || input_nft[0].hash != output_nft[0].hash {
-                    return Err(Error::ErrWrongResult);
+                for equipment in items_1.iter() {
+                    stats_1.equip(&equipment.item.bonus);
                 }
-                return Ok(());
-            } else if who_win == 2 {
-                //2 Win! 检查逻辑类似1
-                let mut loser_fishes = input_nft[0].fishes - stats_1.atk as i32 / 10;
-                if loser_fishes == 0 {
-                    loser_fishes = 999
-                }
-                let winner_fishes = { input_nft[1].fishes + stats_0.hp as i32 / 10 };
-                // debug!(
-                //     "1 Win, loser_fishes:{}, winner_fishes:{}",
-                //     loser_fishes, winner_fishes
-                // );
-                if (output_nft[1].fishes != winner_fishes) || (output_nft[0].fishes != loser_fishes)
-                {
-                    return Err(Error::ErrWrongResult);
-                }
-                let lock_args = load_cell_lock_hash(1, Source::GroupInput)?;
-                let mut conc = Vec::with_capacity(20 + lock_args.len());
-                conc.extend(input_nft[0].hash.iter());
-                conc.extend(lock_args.iter());
-                let res = hash::blake2b_160(conc);
-
-                //检验Hash是否相等
-                if !res.eq(&output_nft[0].hash) || input_nft[1].hash != output_nft[1].hash {
-                    return Err(Error::ErrWrongResult);
-                }
-
-                return Ok(());
+                let items: Vec<_> = items_0.iter().chain(items_1.iter()).collect();
+                check_potions_burned(&item_type_hash, &items)?;
             }
+            //debug!("stats_0:{:?},stats_1:{:?}", stats_0, stats_1);
 
-            //没产生结果，报错
-            return Err(Error::ErrWrongResult);
+            return check_fight(
+                n,
+                &params,
+                &[stats_0, stats_1],
+                &input_nft,
+                &output_nft,
+                &[args_input1, args_input2],
+            );
         }
     }
 
-    //否则就是转账逻辑，一一检查是否相等
-    if input_nft
-        .into_iter()
-        .zip(output_nft)
-        .find(|(input, output)| {
-            if *input != *output {
-                return true;
+    //否则就是转账逻辑，一一检查是否相等，名字不同的视为改名，
+    //只有扩展记录不同的视为修改扩展记录，fish为负数的视为复活
+    let fee = rename_fee(&args)?;
+    let mut revived = 0;
+    for (i, (input, output)) in input_nft.iter().zip(output_nft.iter()).enumerate() {
+        if input == output {
+            continue;
+        }
+        if input.name != output.name {
+            if paused {
+                return Err(Error::ErrPaused);
             }
-            return false;
-        })
-        .is_some()
-    {
-        return Err(Error::ErrWrongTransfer);
+            check_rename(i, input, output, fee, &rental_code_hash)?;
+        } else if is_extensions_update(input, output) {
+            check_update(i, input, output, &rental_code_hash)?;
+        } else if input.fishes < 0 {
+            if paused {
+                return Err(Error::ErrPaused);
+            }
+            check_revive(i, input, output, &revive, &rental_code_hash)?;
+            revived += 1;
+        } else {
+            return Err(Error::ErrWrongTransfer);
+        }
+    }
+    if revived > 0 {
+        //有配置cell时由管理员收取，付给配置cell的lock；否则付给args中的Owner
+        let config_lock_hash = config.as_ref().map(|config| config.lock_hash());
+        let fee_lock_hash = match &config_lock_hash {
+            Some(lock_hash) => &lock_hash[..],
+            None => owner_lock_hash(&args),
+        };
+        check_revive_fee(fee_lock_hash, &revive, revived)?;
     }
 
     Ok(())
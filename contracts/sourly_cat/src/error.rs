@@ -1,4 +1,5 @@
 use ckb_std::error::SysError;
+use sourly_cat_core::error::Error as CoreError;
 
 /// Error
 #[repr(i8)]
@@ -12,6 +13,20 @@ pub enum Error {
     ErrUnmatchPlayer,
     ErrWrongResult,
     ErrWrongTransfer,
+    ErrWrongRename,
+    ErrInvalidName,
+    ErrConfigMissing,
+    ErrInvalidConfig,
+    ErrPaused,
+    ErrWrongSeason,
+    ErrKnockedOut,
+    ErrWrongRevive,
+    ErrInvalidItem,
+    ErrDuplicateItem,
+    ErrPotionNotBurned,
+    ErrInvalidExtension,
+    ErrWrongExtension,
+    ErrUnassignedPotion,
 }
 
 impl From<SysError> for Error {
@@ -27,3 +42,18 @@ impl From<SysError> for Error {
     }
 }
 
+//数据和规则的错误，对应的错误码不变
+impl From<CoreError> for Error {
+    fn from(err: CoreError) -> Self {
+        match err {
+            CoreError::InvalidData => Self::Encoding,
+            CoreError::InvalidExtension => Self::ErrInvalidExtension,
+            CoreError::InvalidName => Self::ErrInvalidName,
+            CoreError::InvalidConfig => Self::ErrInvalidConfig,
+            CoreError::InvalidItem => Self::ErrInvalidItem,
+            CoreError::WrongSeason => Self::ErrWrongSeason,
+            CoreError::KnockedOut => Self::ErrKnockedOut,
+            CoreError::Overflow | CoreError::Draw => Self::ErrWrongResult,
+        }
+    }
+}
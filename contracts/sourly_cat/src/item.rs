@@ -0,0 +1,135 @@
+// Import heap related library from `alloc`
+// https://doc.rust-lang.org/alloc/index.html
+use alloc::vec::Vec;
+
+use ckb_std::{
+    ckb_constants::Source,
+    ckb_types::prelude::*,
+    high_level::{
+        load_cell_data, load_cell_lock_hash, load_cell_type_hash, load_witness_args, QueryIter,
+    },
+};
+
+use sourly_cat_core::item::{Item, ITEM_DATA_LEN};
+
+use crate::error::Error;
+
+//战斗双方的猫
+const FIGHTERS: usize = 2;
+
+//猫带着的一件装备
+pub struct Equipment {
+    pub item: Item,
+    //作为输入时的位置，cell dep中的装备为None
+    pub input: Option<usize>,
+}
+
+//从cell dep和输入中找出双方的装备：装备属于lock与它相同的猫，每种装备只能带一件；
+//药水必须作为输入被销毁，cell dep中的药水不生效，每瓶药水只能给一只猫喝，见potion_owner，
+//是否销毁由check_potions_burned检查
+pub fn load_equipment(
+    item_type_hash: &[u8],
+    lock_hashes: &[[u8; 32]; FIGHTERS],
+) -> Result<[Vec<Equipment>; FIGHTERS], Error> {
+    let mut items: [Vec<Equipment>; FIGHTERS] = [Vec::new(), Vec::new()];
+    for source in [Source::CellDep, Source::Input].iter() {
+        let cells = QueryIter::new(load_cell_type_hash, *source)
+            .zip(QueryIter::new(load_cell_lock_hash, *source))
+            .enumerate()
+            .filter(|(_, (type_hash, lock))| match type_hash {
+                Some(type_hash) => {
+                    type_hash[..] == item_type_hash[..] && lock_hashes.contains(lock)
+                }
+                None => false,
+            })
+            .map(|(i, (_, lock))| (i, lock))
+            .collect::<Vec<_>>();
+        for (i, lock) in cells {
+            let input = if *source == Source::Input {
+                Some(i)
+            } else {
+                None
+            };
+            let item = Item::from_slice(&load_cell_data(i, *source)?)?;
+            let owners = match (item.is_potion(), input) {
+                (true, None) => continue,
+                (true, Some(input)) => alloc::vec![potion_owner(input, &lock, lock_hashes)?],
+                (false, _) => (0..FIGHTERS).filter(|&k| lock_hashes[k] == lock).collect(),
+            };
+            for k in owners {
+                if items[k]
+                    .iter()
+                    .any(|equipped| equipped.item.kind == item.kind)
+                {
+                    return Err(Error::ErrDuplicateItem);
+                }
+                items[k].push(Equipment {
+                    item: item.clone(),
+                    input,
+                });
+            }
+        }
+    }
+    Ok(items)
+}
+
+//喝药水的猫：药水的见证的input_type是1 byte的猫的位置(0或1)，那只猫的lock必须与药水相同；
+//没有指定时药水属于lock相同的那只猫，双方是同一个lock时无法确定，交易不合法
+fn potion_owner(
+    index: usize,
+    lock_hash: &[u8; 32],
+    lock_hashes: &[[u8; 32]; FIGHTERS],
+) -> Result<usize, Error> {
+    let owners = (0..FIGHTERS)
+        .filter(|&k| lock_hashes[k] == *lock_hash)
+        .collect::<Vec<_>>();
+    let assigned: Option<Vec<u8>> = match load_witness_args(index, Source::Input) {
+        Ok(witness) => witness.input_type().to_opt().map(|data| data.unpack()),
+        Err(_) => None,
+    };
+    match assigned {
+        Some(data) if data.len() == 1 && owners.contains(&(data[0] as usize)) => {
+            Ok(data[0] as usize)
+        }
+        None if owners.len() == 1 => Ok(owners[0]),
+        _ => Err(Error::ErrUnassignedPotion),
+    }
+}
+
+//战斗中喝掉的药水不能出现在输出中。输出只能原样转移输入的装备，所以同样数据的药水，
+//输出的数量加上喝掉的数量不能超过输入的数量，同一个交易中转移的其他药水不受影响
+pub fn check_potions_burned(item_type_hash: &[u8], items: &[&Equipment]) -> Result<(), Error> {
+    //每瓶药水只分给了一只猫，不会重复
+    let drunk: Vec<[u8; ITEM_DATA_LEN]> = items
+        .iter()
+        .filter(|equipment| equipment.item.is_potion() && equipment.input.is_some())
+        .map(|equipment| equipment.item.serialize())
+        .collect();
+    for data in drunk.iter() {
+        let count = drunk.iter().filter(|other| *other == data).count();
+        let inputs = count_items(item_type_hash, data, Source::Input)?;
+        let outputs = count_items(item_type_hash, data, Source::Output)?;
+        if outputs + count > inputs {
+            return Err(Error::ErrPotionNotBurned);
+        }
+    }
+    Ok(())
+}
+//source中数据为data的装备数量
+fn count_items(item_type_hash: &[u8], data: &[u8], source: Source) -> Result<usize, Error> {
+    let cells = QueryIter::new(load_cell_type_hash, source)
+        .enumerate()
+        .filter(|(_, type_hash)| match type_hash {
+            Some(type_hash) => type_hash[..] == item_type_hash[..],
+            None => false,
+        })
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    let mut count = 0;
+    for i in cells {
+        if load_cell_data(i, source)?[..] == data[..] {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
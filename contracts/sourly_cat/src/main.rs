@@ -11,9 +11,10 @@
 #![feature(panic_info_message)]
 
 // define modules
+mod config;
 mod entry;
 mod error;
-mod hash;
+mod item;
 
 use ckb_std::{
     default_alloc,
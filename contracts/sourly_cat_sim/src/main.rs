@@ -0,0 +1,28 @@
+//! sourly_cat合约的本机版本
+//!
+//! 模块直接引用合约的源文件，syscalls由ckb-x64-simulator实现：
+//! CKB_TX_FILE是测试导出的交易，CKB_RUNNING_SETUP指定运行哪个脚本。
+//! 退出码与合约的返回值相同，见`error.rs`
+
+extern crate alloc;
+
+#[path = "../../sourly_cat/src/config.rs"]
+mod config;
+#[path = "../../sourly_cat/src/entry.rs"]
+mod entry;
+#[path = "../../sourly_cat/src/error.rs"]
+mod error;
+#[path = "../../sourly_cat/src/item.rs"]
+mod item;
+
+//与合约的program_entry一致
+fn program_entry() -> i8 {
+    match entry::main() {
+        Ok(_) => 0,
+        Err(err) => err as i8,
+    }
+}
+
+fn main() {
+    std::process::exit(program_entry() as i32);
+}
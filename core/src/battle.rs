@@ -0,0 +1,254 @@
+// Import heap related library from `alloc`
+// https://doc.rust-lang.org/alloc/index.html
+use alloc::vec::Vec;
+
+use crate::config::Season;
+use crate::error::Error;
+use crate::hash::blake2b_160;
+use crate::model::NFTData;
+
+//参数集的版本号，放在配置cell中参数部分的第一个字节
+const PARAMS_VERSION_1: u8 = 1;
+//version 1 byte + 防御基数 2 bytes + 幸运系数 1 byte + 血量系数 1 byte
+// + 输家fish除数 1 byte + 赢家fish除数 1 byte + 隐藏奖励 4 bytes，均为大端序
+pub const PARAMS_V1_LEN: usize = 11;
+
+//装备后单项属性的上限
+const MAX_STAT: u8 = 100;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Statistics {
+    pub hp: u8,
+    pub atk: u8,
+    pub def: u8,
+    pub lck: u8,
+}
+
+impl From<[u8; 20]> for Statistics {
+    fn from(hash: [u8; 20]) -> Self {
+        //只看每五个字节的最后一个字节
+        let hp = hash[4] % 100 + 1;
+        let atk = hash[9] % 100 + 1;
+        let def = hash[14] % 100 + 1;
+        let lck = hash[19] % 100 + 1;
+        Statistics { hp, atk, def, lck }
+    }
+}
+
+impl Statistics {
+    //加上装备的属性，每项不超过上限
+    pub fn equip(&mut self, bonus: &Statistics) {
+        self.hp = self.hp.saturating_add(bonus.hp).min(MAX_STAT);
+        self.atk = self.atk.saturating_add(bonus.atk).min(MAX_STAT);
+        self.def = self.def.saturating_add(bonus.def).min(MAX_STAT);
+        self.lck = self.lck.saturating_add(bonus.lck).min(MAX_STAT);
+    }
+}
+
+//游戏参数，没有配置时使用默认值
+#[derive(Clone, Debug, PartialEq)]
+pub struct Params {
+    pub def_base: u16,
+    pub lck_factor: u8,
+    pub hp_factor: u8,
+    pub loser_divisor: u8,
+    pub winner_divisor: u8,
+    pub hidden_reward: i32,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            def_base: 250,
+            lck_factor: 2,
+            hp_factor: 5,
+            loser_divisor: 10,
+            winner_divisor: 10,
+            hidden_reward: 999,
+        }
+    }
+}
+
+impl Params {
+    pub fn from_slice(data: &[u8]) -> Result<Self, Error> {
+        if data.len() < PARAMS_V1_LEN || data[0] != PARAMS_VERSION_1 {
+            return Err(Error::InvalidConfig);
+        }
+        let params = Params {
+            def_base: u16::from_be_bytes([data[1], data[2]]),
+            lck_factor: data[3],
+            hp_factor: data[4],
+            loser_divisor: data[5],
+            winner_divisor: data[6],
+            hidden_reward: i32::from_be_bytes([data[7], data[8], data[9], data[10]]),
+        };
+        if params.hp_factor == 0 || params.loser_divisor == 0 || params.winner_divisor == 0 {
+            return Err(Error::InvalidConfig);
+        }
+        Ok(params)
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(PARAMS_V1_LEN);
+        buf.push(PARAMS_VERSION_1);
+        buf.extend(self.def_base.to_be_bytes().iter());
+        buf.push(self.lck_factor);
+        buf.push(self.hp_factor);
+        buf.push(self.loser_divisor);
+        buf.push(self.winner_divisor);
+        buf.extend(self.hidden_reward.to_be_bytes().iter());
+        buf
+    }
+
+    //攻击伤害 Hurt1 = ATK1 - ATK1*DEF2/(DEF2 - LCK2*lck_factor + def_base)
+    pub fn hurt(&self, attacker: &Statistics, defender: &Statistics) -> Result<u16, Error> {
+        let atk = attacker.atk as u32;
+        let divisor = (self.def_base as u32 + defender.def as u32)
+            .checked_sub(defender.lck as u32 * self.lck_factor as u32)
+            .filter(|divisor| *divisor > 0)
+            .ok_or(Error::InvalidConfig)?;
+        Ok(atk.saturating_sub(atk * defender.def as u32 / divisor) as u16)
+    }
+
+    //n个回合的伤害能否打倒对方
+    pub fn is_defeated(&self, n: u16, hurt: u16, defender: &Statistics) -> bool {
+        n as u32 * hurt as u32 >= self.hp_factor as u32 * defender.hp as u32
+    }
+
+    //第n回合分出胜负时返回赢的一方，hurts是双方的攻击伤害
+    //n * Hurt1 >= k * HP2 且 (n-1) * Hurt2 < k * HP1 则 0 胜利
+    //n * Hurt1 < k * HP2 且 n * Hurt2 >= k * HP1 则 1 胜利
+    //两个条件互斥，都不满足时没产生结果
+    pub fn round_winner(&self, n: u16, hurts: &[u16; 2], stats: &[Statistics; 2]) -> Option<usize> {
+        if n == 0 {
+            return None;
+        }
+        let defeated_1 = self.is_defeated(n, hurts[0], &stats[1]);
+        if defeated_1 && !self.is_defeated(n - 1, hurts[1], &stats[0]) {
+            Some(0)
+        } else if !defeated_1 && self.is_defeated(n, hurts[1], &stats[0]) {
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    //计算输的一方有多少fish，暂时没考虑四舍五入
+    pub fn loser_fishes(&self, fishes: i32, winner: &Statistics) -> i32 {
+        //触发隐藏奖励
+        if self.is_hidden_reward(fishes, winner) {
+            return self.hidden_reward;
+        }
+        fishes - winner.atk as i32 / self.loser_divisor as i32
+    }
+
+    //输的一方fish恰好输光时触发隐藏奖励
+    pub fn is_hidden_reward(&self, fishes: i32, winner: &Statistics) -> bool {
+        fishes - winner.atk as i32 / self.loser_divisor as i32 == 0
+    }
+
+    //计算赢的一方的Fish数目，超出i32的范围时这场战斗不合法
+    pub fn winner_fishes(&self, fishes: i32, loser: &Statistics) -> Result<i32, Error> {
+        fishes
+            .checked_add(loser.hp as i32 / self.winner_divisor as i32)
+            .ok_or(Error::Overflow)
+    }
+}
+
+//输的一方的新hash，blake160(hash + 赢的一方的lock hash)
+pub fn loser_hash(hash: &[u8; 20], winner_lock_hash: &[u8]) -> [u8; 20] {
+    let mut conc = Vec::with_capacity(20 + winner_lock_hash.len());
+    conc.extend(hash.iter());
+    conc.extend(winner_lock_hash.iter());
+    blake2b_160(conc)
+}
+
+//winner赢了之后双方的数据：fish、hash、战绩和世代，其他字段不变
+pub fn settle(
+    inputs: &[NFTData; 2],
+    winner: usize,
+    lock_hashes: [&[u8]; 2],
+    stats: &[Statistics; 2],
+    params: &Params,
+) -> Result<[NFTData; 2], Error> {
+    let loser = 1 - winner;
+    let mut outputs = inputs.clone();
+    let hidden_reward = params.is_hidden_reward(inputs[loser].fishes, &stats[winner]);
+    outputs[winner].fishes = params.winner_fishes(inputs[winner].fishes, &stats[loser])?;
+    outputs[loser].fishes = params.loser_fishes(inputs[loser].fishes, &stats[winner]);
+
+    //输的一方要更改Hash，并成为下一代，赢的一方不变
+    outputs[loser].hash = loser_hash(&inputs[loser].hash, lock_hashes[winner]);
+    outputs[loser].generation = inputs[loser]
+        .generation
+        .checked_add(1)
+        .ok_or(Error::Overflow)?;
+    outputs[loser].prev_hash = inputs[loser].hash;
+
+    outputs[winner].fought(true, false);
+    outputs[loser].fought(false, hidden_reward);
+    Ok(outputs)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FightResult {
+    //写进见证的战斗回合数，也就是分出胜负的最小回合数
+    pub rounds: u16,
+    //赢的一方，0或1
+    pub winner: usize,
+    //战斗后双方的数据
+    pub outputs: [NFTData; 2],
+}
+
+//找出分出胜负的最小回合数，输入必须已经迁移到当前赛季
+pub fn resolve(
+    inputs: &[NFTData; 2],
+    lock_hashes: [&[u8]; 2],
+    stats: &[Statistics; 2],
+    params: &Params,
+    max_rounds: u16,
+) -> Result<FightResult, Error> {
+    let hurts = [
+        params.hurt(&stats[0], &stats[1])?,
+        params.hurt(&stats[1], &stats[0])?,
+    ];
+    for n in 1..=max_rounds {
+        if let Some(winner) = params.round_winner(n, &hurts, stats) {
+            return Ok(FightResult {
+                rounds: n,
+                winner,
+                outputs: settle(inputs, winner, lock_hashes, stats, params)?,
+            });
+        }
+    }
+    Err(Error::Draw)
+}
+
+//按照合约的规则计算战斗结果，双方的属性由hash决定
+pub fn fight(
+    inputs: &[NFTData; 2],
+    lock_hashes: [&[u8]; 2],
+    params: &Params,
+    season: &Season,
+    max_rounds: u16,
+) -> Result<FightResult, Error> {
+    let stats = [inputs[0].hash.into(), inputs[1].hash.into()];
+    fight_with_stats(inputs, lock_hashes, stats, params, season, max_rounds)
+}
+
+//同上，双方的属性由调用者给出，例如加上了装备
+pub fn fight_with_stats(
+    inputs: &[NFTData; 2],
+    lock_hashes: [&[u8]; 2],
+    stats: [Statistics; 2],
+    params: &Params,
+    season: &Season,
+    max_rounds: u16,
+) -> Result<FightResult, Error> {
+    //新赛季的第一场战斗先迁移
+    let migrated = [inputs[0].migrate(season)?, inputs[1].migrate(season)?];
+    if migrated.iter().any(|nft| nft.is_knocked_out()) {
+        return Err(Error::KnockedOut);
+    }
+    resolve(&migrated, lock_hashes, &stats, params, max_rounds)
+}
@@ -0,0 +1,196 @@
+// Import heap related library from `alloc`
+// https://doc.rust-lang.org/alloc/index.html
+use alloc::{vec, vec::Vec};
+
+use crate::battle::{Params, PARAMS_V1_LEN};
+use crate::error::Error;
+
+const LOCK_HASH_LEN: usize = 32;
+const TYPE_HASH_LEN: usize = 32;
+//赛季号 4 bytes + 新赛季的初始fish 4 bytes，均为大端序
+const SEASON_LEN: usize = 8;
+//复活费用 8 bytes(shannons) + 复活后的fish 4 bytes，均为大端序
+const REVIVE_LEN: usize = 12;
+
+//标志位：暂停战斗以及所有改变fish的操作
+pub const FLAG_PAUSED: u8 = 0x01;
+
+//当前赛季，没有配置时为第0赛季
+#[derive(Clone, Debug, PartialEq)]
+pub struct Season {
+    pub number: u32,
+    pub base_fishes: i32,
+}
+
+impl Default for Season {
+    fn default() -> Self {
+        Season {
+            number: 0,
+            base_fishes: 100,
+        }
+    }
+}
+
+impl Season {
+    pub fn from_slice(data: &[u8]) -> Result<Self, Error> {
+        if data.len() < SEASON_LEN {
+            return Err(Error::InvalidConfig);
+        }
+        let season = Season {
+            number: u32::from_be_bytes([data[0], data[1], data[2], data[3]]),
+            base_fishes: i32::from_be_bytes([data[4], data[5], data[6], data[7]]),
+        };
+        //重置后的猫必须还能战斗
+        if season.base_fishes < 0 {
+            return Err(Error::InvalidConfig);
+        }
+        Ok(season)
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buf = self.number.to_be_bytes().to_vec();
+        buf.extend(self.base_fishes.to_be_bytes().iter());
+        buf
+    }
+}
+
+//fish为负数的猫，主人向配置cell的lock支付费用后可以复活
+#[derive(Clone, Debug, PartialEq)]
+pub struct Revive {
+    pub fee: u64,
+    pub fishes: i32,
+}
+
+impl Default for Revive {
+    fn default() -> Self {
+        Revive {
+            //100 CKB
+            fee: 100_0000_0000,
+            fishes: 10,
+        }
+    }
+}
+
+impl Revive {
+    pub fn from_slice(data: &[u8]) -> Result<Self, Error> {
+        if data.len() < REVIVE_LEN {
+            return Err(Error::InvalidConfig);
+        }
+        let mut fee = [0u8; 8];
+        fee.copy_from_slice(&data[0..8]);
+        let revive = Revive {
+            fee: u64::from_be_bytes(fee),
+            fishes: i32::from_be_bytes([data[8], data[9], data[10], data[11]]),
+        };
+        //复活后的猫必须能战斗
+        if revive.fishes < 0 {
+            return Err(Error::InvalidConfig);
+        }
+        Ok(revive)
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buf = self.fee.to_be_bytes().to_vec();
+        buf.extend(self.fishes.to_be_bytes().iter());
+        buf
+    }
+}
+
+//从start开始的32字节hash，数据在start之前结束时为None
+fn read_hash(data: &[u8], start: usize) -> Result<Option<[u8; 32]>, Error> {
+    if data.len() <= start {
+        return Ok(None);
+    }
+    if data.len() < start + TYPE_HASH_LEN {
+        return Err(Error::InvalidConfig);
+    }
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&data[start..start + TYPE_HASH_LEN]);
+    Ok(Some(hash))
+}
+
+//配置cell的data，通过cell dep引用，用type hash(一般是type id)定位
+//data: 阈值 1 byte + 管理员数量 1 byte + 管理员 lock hash 32 bytes * 数量
+// + 标志位 1 byte(可选) + 游戏参数(可选，格式见battle::Params)
+// + 赛季(可选，必须跟在游戏参数后面) + 复活(可选，必须跟在赛季后面)
+// + 装备合约的type hash 32 bytes(可选，必须跟在复活后面，全0表示不使用装备)
+// + 租借lock的code hash 32 bytes(可选，必须跟在装备后面)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Config {
+    pub threshold: u8,
+    pub admin_lock_hashes: Vec<[u8; 32]>,
+    pub flags: u8,
+    pub params: Params,
+    pub season: Season,
+    pub revive: Revive,
+    pub item_type_hash: Option<[u8; 32]>,
+    pub rental_code_hash: Option<[u8; 32]>,
+}
+
+impl Config {
+    pub fn from_slice(data: &[u8]) -> Result<Self, Error> {
+        if data.len() < 2 {
+            return Err(Error::InvalidConfig);
+        }
+        let threshold = data[0];
+        let count = data[1] as usize;
+        let admins_end = 2 + count * LOCK_HASH_LEN;
+        if threshold == 0 || threshold as usize > count || data.len() < admins_end {
+            return Err(Error::InvalidConfig);
+        }
+        let admin_lock_hashes = data[2..admins_end]
+            .chunks(LOCK_HASH_LEN)
+            .map(|chunk| {
+                let mut lock_hash = [0u8; 32];
+                lock_hash.copy_from_slice(chunk);
+                lock_hash
+            })
+            .collect();
+        let mut config = Config {
+            threshold,
+            admin_lock_hashes,
+            flags: data.get(admins_end).cloned().unwrap_or(0),
+            ..Default::default()
+        };
+        let params_start = admins_end + 1;
+        if data.len() > params_start {
+            config.params = Params::from_slice(&data[params_start..])?;
+        }
+        let season_start = params_start + PARAMS_V1_LEN;
+        if data.len() > season_start {
+            config.season = Season::from_slice(&data[season_start..])?;
+        }
+        let revive_start = season_start + SEASON_LEN;
+        if data.len() > revive_start {
+            config.revive = Revive::from_slice(&data[revive_start..])?;
+        }
+        let item_start = revive_start + REVIVE_LEN;
+        config.item_type_hash = read_hash(data, item_start)?.filter(|hash| *hash != [0u8; 32]);
+        config.rental_code_hash = read_hash(data, item_start + TYPE_HASH_LEN)?;
+        Ok(config)
+    }
+
+    //总是写出所有部分
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buf = vec![self.threshold, self.admin_lock_hashes.len() as u8];
+        for lock_hash in self.admin_lock_hashes.iter() {
+            buf.extend(lock_hash.iter());
+        }
+        buf.push(self.flags);
+        buf.extend(self.params.serialize());
+        buf.extend(self.season.serialize());
+        buf.extend(self.revive.serialize());
+        //全0表示不使用装备
+        if self.item_type_hash.is_some() || self.rental_code_hash.is_some() {
+            buf.extend(self.item_type_hash.unwrap_or_default().iter());
+        }
+        if let Some(rental_code_hash) = &self.rental_code_hash {
+            buf.extend(rental_code_hash.iter());
+        }
+        buf
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.flags & FLAG_PAUSED != 0
+    }
+}
@@ -0,0 +1,44 @@
+use core::fmt;
+
+//合约把这些错误映射到自己的错误码，见contracts/sourly_cat/src/error.rs
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    //cell data的长度不合法
+    InvalidData,
+    //扩展记录不合法
+    InvalidExtension,
+    //名字不合法
+    InvalidName,
+    //配置cell的data不合法
+    InvalidConfig,
+    //装备cell的data不合法
+    InvalidItem,
+    //猫的赛季比当前赛季新
+    WrongSeason,
+    //fish为负数的猫不能战斗
+    KnockedOut,
+    //世代等计数或者赢的一方的fish溢出
+    Overflow,
+    //在最大回合数内分不出胜负
+    Draw,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match self {
+            Error::InvalidData => "invalid cat data",
+            Error::InvalidExtension => "invalid cat extension records",
+            Error::InvalidName => "invalid cat name",
+            Error::InvalidConfig => "invalid config data",
+            Error::InvalidItem => "invalid item data",
+            Error::WrongSeason => "cat is from a later season than the config",
+            Error::KnockedOut => "knocked out cats can't fight",
+            Error::Overflow => "cat counter or fishes overflow",
+            Error::Draw => "no winner within the max rounds",
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
@@ -32,4 +32,4 @@ pub fn blake2b_160<T: AsRef<[u8]>>(s: T) -> [u8; 20] {
     let hash = blake2b_256(s);
     result.copy_from_slice(&hash[0..20]);
     result
-}
\ No newline at end of file
+}
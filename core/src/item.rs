@@ -0,0 +1,54 @@
+use crate::battle::Statistics;
+use crate::error::Error;
+
+//装备cell的data，格式与cat_item合约一致
+//种类 1 byte + hp 1 byte + atk 1 byte + def 1 byte + lck 1 byte
+pub const ITEM_DATA_LEN: usize = 5;
+
+//装备的种类：项圈，爪套，盔甲
+pub const ITEM_KIND_COLLAR: u8 = 1;
+pub const ITEM_KIND_CLAWS: u8 = 2;
+pub const ITEM_KIND_ARMOUR: u8 = 3;
+//药水，只在消耗掉它的那场战斗中生效
+pub const ITEM_KIND_POTION: u8 = 4;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Item {
+    pub kind: u8,
+    pub bonus: Statistics,
+}
+
+impl Item {
+    pub fn from_slice(data: &[u8]) -> Result<Self, Error> {
+        if data.len() != ITEM_DATA_LEN {
+            return Err(Error::InvalidItem);
+        }
+        match data[0] {
+            ITEM_KIND_COLLAR | ITEM_KIND_CLAWS | ITEM_KIND_ARMOUR | ITEM_KIND_POTION => {}
+            _ => return Err(Error::InvalidItem),
+        }
+        Ok(Item {
+            kind: data[0],
+            bonus: Statistics {
+                hp: data[1],
+                atk: data[2],
+                def: data[3],
+                lck: data[4],
+            },
+        })
+    }
+
+    pub fn serialize(&self) -> [u8; ITEM_DATA_LEN] {
+        [
+            self.kind,
+            self.bonus.hp,
+            self.bonus.atk,
+            self.bonus.def,
+            self.bonus.lck,
+        ]
+    }
+
+    pub fn is_potion(&self) -> bool {
+        self.kind == ITEM_KIND_POTION
+    }
+}
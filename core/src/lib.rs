@@ -0,0 +1,26 @@
+//! sourly_cat的规则，合约和链下工具共用一份实现
+//!
+//! `model.rs` 猫的数据格式
+//! `config.rs` 配置cell的格式
+//! `item.rs` 装备的数据格式
+//! `battle.rs` 属性、游戏参数和战斗的计算
+//! `hash.rs` blake2b，与CKB一致
+
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod battle;
+pub mod config;
+pub mod error;
+pub mod hash;
+pub mod item;
+pub mod model;
+
+pub use battle::{FightResult, Params, Statistics};
+pub use config::{Config, Revive, Season};
+pub use error::Error;
+pub use item::Item;
+pub use model::{Extension, NFTData};
@@ -0,0 +1,271 @@
+// Import heap related library from `alloc`
+// https://doc.rust-lang.org/alloc/index.html
+use alloc::{string::String, vec::Vec};
+use core::str;
+
+use crate::config::Season;
+use crate::error::Error;
+use crate::hash::blake2b_160;
+
+pub const NAME_LEN: usize = 16;
+//最早的数据：name 16 bytes + hash 20 bytes + fishes 4 bytes
+pub const MIN_NFT_DATA_LEN: usize = 40;
+//赛季 4 bytes + 上赛季的fish 4 bytes，旧的40字节数据视为第0赛季
+pub const SEASON_DATA_LEN: usize = MIN_NFT_DATA_LEN + 8;
+//徽章 2 bytes + 连胜次数 1 byte
+pub const BADGE_DATA_LEN: usize = SEASON_DATA_LEN + 3;
+//胜场 4 bytes + 负场 4 bytes
+pub const RECORD_DATA_LEN: usize = BADGE_DATA_LEN + 8;
+//世代 4 bytes + 上一代的hash 20 bytes，铸造时为第0代，上一代的hash为0
+pub const LINEAGE_DATA_LEN: usize = RECORD_DATA_LEN + 24;
+//允许的数据长度，缺少的字段视为0；完整的固定字段共83 bytes，后面可以跟扩展记录
+const NFT_DATA_LENS: [usize; 5] = [
+    MIN_NFT_DATA_LEN,
+    SEASON_DATA_LEN,
+    BADGE_DATA_LEN,
+    RECORD_DATA_LEN,
+    LINEAGE_DATA_LEN,
+];
+
+//铸造时的初始fish
+pub const DEFAULT_FISHES: i32 = 100;
+
+//徽章，只能由战斗设置
+//第一次胜利
+pub const BADGE_FIRST_WIN: u16 = 0x0001;
+//连胜达到WIN_STREAK_FOR_BADGE次
+pub const BADGE_WIN_STREAK: u16 = 0x0002;
+//输了之后触发隐藏奖励
+pub const BADGE_HIDDEN_REWARD: u16 = 0x0004;
+//输了之后fish为负数
+pub const BADGE_NEGATIVE_SURVIVOR: u16 = 0x0008;
+pub const WIN_STREAK_FOR_BADGE: u8 = 3;
+
+//扩展记录：类型 1 byte + 长度 2 bytes(大端序) + 内容，跟在固定字段后面
+const EXTENSION_HEADER_LEN: usize = 3;
+
+//图片URI
+pub const EXTENSION_IMAGE: u8 = 1;
+//描述
+pub const EXTENSION_DESCRIPTION: u8 = 2;
+//属性，和其他类型一样铸造后不能修改
+pub const EXTENSION_ATTRIBUTES: u8 = 3;
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct Extension {
+    pub kind: u8,
+    pub value: Vec<u8>,
+}
+
+impl Extension {
+    //图片和描述可以由主人修改，属性和未知类型的记录都不能修改
+    pub fn is_mutable(&self) -> bool {
+        self.kind == EXTENSION_IMAGE || self.kind == EXTENSION_DESCRIPTION
+    }
+}
+
+//战绩：徽章，连胜次数，胜场，负场
+pub type Record = (u16, u8, u32, u32);
+
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct NFTData {
+    pub name: [u8; NAME_LEN],
+    pub hash: [u8; 20],
+    pub fishes: i32,
+    pub season: u32,
+    pub last_season_fishes: i32,
+    pub badges: u16,
+    pub win_streak: u8,
+    pub wins: u32,
+    pub losses: u32,
+    pub generation: u32,
+    pub prev_hash: [u8; 20],
+    pub extensions: Vec<Extension>,
+}
+
+//从data[start..end]读取大端序的整数，end不超过4个字节
+fn be_field(data: &[u8], start: usize, end: usize) -> [u8; 4] {
+    let mut buf = [0u8; 4];
+    buf[4 - (end - start)..].copy_from_slice(&data[start..end]);
+    buf
+}
+
+impl NFTData {
+    //铸造一只新猫，hash = blake160(名字 + 主人的lock hash)
+    pub fn mint(name: &str, lock_hash: &[u8]) -> Result<Self, Error> {
+        let name = encode_name(name)?;
+        let mut conc = Vec::with_capacity(NAME_LEN + lock_hash.len());
+        conc.extend(name.iter().take_while(|b| **b != 0));
+        conc.extend(lock_hash.iter());
+        Ok(NFTData {
+            name,
+            hash: blake2b_160(conc),
+            fishes: DEFAULT_FISHES,
+            ..Default::default()
+        })
+    }
+
+    //采用大端序，长度不合法时为InvalidData
+    pub fn from_slice(data: &[u8]) -> Result<Self, Error> {
+        if !NFT_DATA_LENS.contains(&data.len()) && data.len() <= LINEAGE_DATA_LEN {
+            return Err(Error::InvalidData);
+        }
+        let mut nft = NFTData::default();
+        nft.name.copy_from_slice(&data[0..16]);
+        nft.hash.copy_from_slice(&data[16..36]);
+        nft.fishes = i32::from_be_bytes(be_field(data, 36, 40));
+        if data.len() >= SEASON_DATA_LEN {
+            nft.season = u32::from_be_bytes(be_field(data, 40, 44));
+            nft.last_season_fishes = i32::from_be_bytes(be_field(data, 44, 48));
+        }
+        if data.len() >= BADGE_DATA_LEN {
+            nft.badges = u32::from_be_bytes(be_field(data, 48, 50)) as u16;
+            nft.win_streak = data[50];
+        }
+        if data.len() >= RECORD_DATA_LEN {
+            nft.wins = u32::from_be_bytes(be_field(data, 51, 55));
+            nft.losses = u32::from_be_bytes(be_field(data, 55, 59));
+        }
+        if data.len() >= LINEAGE_DATA_LEN {
+            nft.generation = u32::from_be_bytes(be_field(data, 59, 63));
+            nft.prev_hash.copy_from_slice(&data[63..83]);
+            nft.extensions = parse_extensions(&data[LINEAGE_DATA_LEN..])?;
+        }
+        Ok(nft)
+    }
+
+    //总是输出完整的格式
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(LINEAGE_DATA_LEN);
+        buf.extend(self.name.iter());
+        buf.extend(self.hash.iter());
+        buf.extend(self.fishes.to_be_bytes().iter());
+        buf.extend(self.season.to_be_bytes().iter());
+        buf.extend(self.last_season_fishes.to_be_bytes().iter());
+        buf.extend(self.badges.to_be_bytes().iter());
+        buf.push(self.win_streak);
+        buf.extend(self.wins.to_be_bytes().iter());
+        buf.extend(self.losses.to_be_bytes().iter());
+        buf.extend(self.generation.to_be_bytes().iter());
+        buf.extend(self.prev_hash.iter());
+        for extension in self.extensions.iter() {
+            buf.push(extension.kind);
+            buf.extend((extension.value.len() as u16).to_be_bytes().iter());
+            buf.extend(extension.value.iter());
+        }
+        buf
+    }
+
+    pub fn name(&self) -> String {
+        let len = self.name.iter().position(|b| *b == 0).unwrap_or(NAME_LEN);
+        String::from_utf8_lossy(&self.name[..len]).into_owned()
+    }
+
+    pub fn is_knocked_out(&self) -> bool {
+        self.fishes < 0
+    }
+
+    //新赛季的第一场战斗，把上赛季的fish存档并重置为初始值
+    pub fn migrate(&self, season: &Season) -> Result<Self, Error> {
+        if self.season > season.number {
+            return Err(Error::WrongSeason);
+        }
+        if self.season == season.number {
+            return Ok(self.clone());
+        }
+        Ok(NFTData {
+            fishes: season.base_fishes,
+            season: season.number,
+            last_season_fishes: self.fishes,
+            ..self.clone()
+        })
+    }
+
+    pub fn record(&self) -> Record {
+        (self.badges, self.win_streak, self.wins, self.losses)
+    }
+
+    //战斗后更新徽章和战绩，fish必须已经是战斗后的数目
+    pub fn fought(&mut self, won: bool, hidden_reward: bool) {
+        if !won {
+            if hidden_reward {
+                self.badges |= BADGE_HIDDEN_REWARD;
+            }
+            if self.fishes < 0 {
+                self.badges |= BADGE_NEGATIVE_SURVIVOR;
+            }
+            self.win_streak = 0;
+            self.losses = self.losses.saturating_add(1);
+            return;
+        }
+        self.win_streak = self.win_streak.saturating_add(1);
+        self.wins = self.wins.saturating_add(1);
+        self.badges |= BADGE_FIRST_WIN;
+        if self.win_streak >= WIN_STREAK_FOR_BADGE {
+            self.badges |= BADGE_WIN_STREAK;
+        }
+    }
+}
+
+//名字必须是可打印的UTF-8字符串，不足16字节的部分用0补齐
+pub fn is_valid_name(name: &[u8; NAME_LEN]) -> bool {
+    let len = name.iter().position(|b| *b == 0).unwrap_or(name.len());
+    if len == 0 || name[len..].iter().any(|b| *b != 0) {
+        return false;
+    }
+    match str::from_utf8(&name[..len]) {
+        Ok(name) => !name.chars().any(|c| c.is_control()),
+        Err(_) => false,
+    }
+}
+
+//按合约的规则编码名字，最多16字节
+pub fn encode_name(name: &str) -> Result<[u8; NAME_LEN], Error> {
+    if name.is_empty() || name.len() > NAME_LEN || name.chars().any(|c| c.is_control()) {
+        return Err(Error::InvalidName);
+    }
+    let mut buf = [0u8; NAME_LEN];
+    buf[..name.len()].copy_from_slice(name.as_bytes());
+    Ok(buf)
+}
+
+//记录必须按类型严格递增排列，每种类型最多一条，类型0保留
+pub fn parse_extensions(data: &[u8]) -> Result<Vec<Extension>, Error> {
+    let mut extensions: Vec<Extension> = Vec::new();
+    let mut rest = data;
+    while !rest.is_empty() {
+        if rest.len() < EXTENSION_HEADER_LEN {
+            return Err(Error::InvalidExtension);
+        }
+        let kind = rest[0];
+        let end = EXTENSION_HEADER_LEN + u16::from_be_bytes([rest[1], rest[2]]) as usize;
+        if rest.len() < end {
+            return Err(Error::InvalidExtension);
+        }
+        let last_kind = extensions
+            .last()
+            .map(|extension| extension.kind)
+            .unwrap_or(0);
+        if kind <= last_kind {
+            return Err(Error::InvalidExtension);
+        }
+        extensions.push(Extension {
+            kind,
+            value: rest[EXTENSION_HEADER_LEN..end].to_vec(),
+        });
+        rest = &rest[end..];
+    }
+    Ok(extensions)
+}
+
+//只增加，删除或修改了可变的记录
+pub fn is_extensions_update_allowed(input: &[Extension], output: &[Extension]) -> bool {
+    let immutable = |extensions: &[Extension]| {
+        extensions
+            .iter()
+            .filter(|extension| !extension.is_mutable())
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    immutable(input) == immutable(output)
+}
@@ -0,0 +1,33 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use sourly_cat_core::model::{is_extensions_update_allowed, parse_extensions, Extension};
+use sourly_cat_fuzz::{check_error, entry::fuzzing::parse_nft_data, error::Error};
+
+//扩展记录编码回字节
+fn encode(extensions: &[Extension]) -> Vec<u8> {
+    let mut data = Vec::new();
+    for extension in extensions.iter() {
+        data.push(extension.kind);
+        data.extend_from_slice(&(extension.value.len() as u16).to_be_bytes());
+        data.extend_from_slice(&extension.value);
+    }
+    data
+}
+
+//任意的cell data：只能解析成功，或者返回Encoding/ErrInvalidExtension
+fuzz_target!(|data: &[u8]| {
+    check_error(
+        parse_nft_data(data.to_vec()),
+        &[Error::Encoding as i8, Error::ErrInvalidExtension as i8],
+    );
+
+    //扩展记录的编码是唯一的，解析成功后重新编码要得到原来的字节
+    if let Some(extensions) = check_error(
+        parse_extensions(data).map_err(Error::from),
+        &[Error::ErrInvalidExtension as i8],
+    ) {
+        assert_eq!(encode(&extensions), data);
+        assert!(is_extensions_update_allowed(&extensions, &extensions));
+    }
+});
@@ -0,0 +1,15 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use sourly_cat_core::config::Config;
+use sourly_cat_fuzz::{check_error, error::Error};
+
+//任意的配置cell data：只能解析成功，或者返回ErrInvalidConfig，能解析的配置重新编码后不变
+fuzz_target!(|data: &[u8]| {
+    if let Some(config) = check_error(
+        Config::from_slice(data).map_err(Error::from),
+        &[Error::ErrInvalidConfig as i8],
+    ) {
+        assert_eq!(Config::from_slice(&config.serialize()), Ok(config));
+    }
+});
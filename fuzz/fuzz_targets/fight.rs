@@ -0,0 +1,71 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use sourly_cat_core::battle::{Params, Statistics, PARAMS_V1_LEN};
+use sourly_cat_fuzz::{
+    check_error,
+    entry::fuzzing::{check_fight, parse_nft_data},
+    error::Error,
+};
+
+//回合数 2 bytes + 双方的lock hash 32 bytes * 2 + 游戏参数 + 4只猫的data
+//(输入0，输入1，输出0，输出1)
+const HEADER_LEN: usize = 2 + 64 + PARAMS_V1_LEN;
+//每只猫的固定字段，剩下的字节作为最后一只猫的扩展记录
+const CAT_LEN: usize = 83;
+
+fn split(data: &[u8]) -> Option<(u16, [[u8; 32]; 2], &[u8], [&[u8]; 4])> {
+    if data.len() < HEADER_LEN + CAT_LEN * 4 {
+        return None;
+    }
+    let (header, cats) = data.split_at(HEADER_LEN);
+    let n = u16::from_be_bytes([header[0], header[1]]);
+    let mut lock_hashes = [[0u8; 32]; 2];
+    lock_hashes[0].copy_from_slice(&header[2..34]);
+    lock_hashes[1].copy_from_slice(&header[34..66]);
+    let cats = [
+        &cats[..CAT_LEN],
+        &cats[CAT_LEN..CAT_LEN * 2],
+        &cats[CAT_LEN * 2..CAT_LEN * 3],
+        &cats[CAT_LEN * 3..],
+    ];
+    Some((n, lock_hashes, &header[66..], cats))
+}
+
+//任意的战斗：验证只能通过，或者返回文档中的错误码
+fuzz_target!(|data: &[u8]| {
+    let (n, lock_hashes, params, cats) = match split(data) {
+        Some(parts) => parts,
+        None => return,
+    };
+    let params = match check_error(
+        Params::from_slice(params).map_err(Error::from),
+        &[Error::ErrInvalidConfig as i8],
+    ) {
+        Some(params) => params,
+        None => return,
+    };
+    let mut nft = Vec::with_capacity(4);
+    for cat in cats.iter() {
+        match check_error(
+            parse_nft_data(cat.to_vec()),
+            &[Error::Encoding as i8, Error::ErrInvalidExtension as i8],
+        ) {
+            Some(cat) => nft.push(cat),
+            None => return,
+        }
+    }
+    //合约在check_fight之前已经拒绝了0回合和fish为负数的猫
+    if n == 0 || cats[0][36] & 0x80 != 0 || cats[1][36] & 0x80 != 0 {
+        return;
+    }
+    let mut hashes = [[0u8; 20]; 2];
+    hashes[0].copy_from_slice(&cats[0][16..36]);
+    hashes[1].copy_from_slice(&cats[1][16..36]);
+    let stats: [Statistics; 2] = [hashes[0].into(), hashes[1].into()];
+    //赢的一方的fish溢出时也是ErrWrongResult
+    check_error(
+        check_fight(n, &params, &stats, &nft[..2], &nft[2..], &lock_hashes),
+        &[Error::ErrWrongResult as i8, Error::ErrInvalidConfig as i8],
+    );
+});
@@ -0,0 +1,18 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use ckb_std::ckb_types::{packed::WitnessArgs, prelude::*};
+use sourly_cat_fuzz::entry::fuzzing::fight_rounds;
+
+//任意的见证：合约用load_witness_args读取，不是合法的WitnessArgs时视为0回合
+fuzz_target!(|data: &[u8]| {
+    let wit_args = match WitnessArgs::from_slice(data) {
+        Ok(wit_args) => wit_args,
+        Err(_) => return,
+    };
+    let n = fight_rounds(&wit_args);
+    if n > 0 {
+        let in_type: Vec<u8> = wit_args.input_type().to_opt().unwrap().unpack();
+        assert_eq!(&n.to_be_bytes()[..], &in_type[..2]);
+    }
+});
@@ -0,0 +1,31 @@
+//! 在本机编译sourly_cat合约的模块，给fuzz target调用
+//!
+//! 模块直接引用合约的源文件，syscalls由ckb-x64-simulator实现；
+//! fuzz target只调用不读取交易的解析和验证函数
+
+extern crate alloc;
+
+#[path = "../../contracts/sourly_cat/src/config.rs"]
+pub mod config;
+#[path = "../../contracts/sourly_cat/src/entry.rs"]
+pub mod entry;
+#[path = "../../contracts/sourly_cat/src/error.rs"]
+pub mod error;
+#[path = "../../contracts/sourly_cat/src/item.rs"]
+pub mod item;
+
+use error::Error;
+
+//错误码必须是allowed中的一个，否则说明出现了没有文档的错误
+pub fn check_error<T>(result: Result<T, Error>, allowed: &[i8]) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(err) => {
+            let code = err as i8;
+            if !allowed.contains(&code) {
+                panic!("undocumented error code {}", code);
+            }
+            None
+        }
+    }
+}
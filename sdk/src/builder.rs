@@ -0,0 +1,446 @@
+use ckb_tool::ckb_types::{
+    bytes::Bytes,
+    core::{TransactionBuilder, TransactionView},
+    packed::{CellDep, CellInput, CellOutput, Script, WitnessArgs},
+    prelude::*,
+};
+
+use crate::battle::{fight_with_stats, FightResult, Params, Statistics};
+use crate::config::{Revive, Season};
+use crate::error::Error;
+use crate::item::Item;
+use crate::model::{encode_name, NFTData};
+
+//合约部署信息：交易需要的cell dep(合约、配置cell等)以及猫的type script
+#[derive(Clone, Debug)]
+pub struct CatDeployment {
+    pub cell_deps: Vec<CellDep>,
+    pub type_script: Script,
+}
+
+impl CatDeployment {
+    fn cat_output(&self, lock: &Script, capacity: u64) -> CellOutput {
+        CellOutput::new_builder()
+            .capacity(capacity.pack())
+            .lock(lock.clone())
+            .type_(Some(self.type_script.clone()).pack())
+            .build()
+    }
+
+    fn builder(&self) -> TransactionBuilder {
+        TransactionBuilder::default().cell_deps(self.cell_deps.clone())
+    }
+}
+
+//链上已有的一只猫
+#[derive(Clone, Debug)]
+pub struct CatCell {
+    pub input: CellInput,
+    pub lock: Script,
+    pub capacity: u64,
+    pub data: NFTData,
+}
+
+impl CatCell {
+    pub fn lock_hash(&self) -> Vec<u8> {
+        self.lock.calc_script_hash().as_slice().to_vec()
+    }
+}
+
+//未签名的交易，以及交易中猫的输出数据(按输出的顺序)
+//签名、手续费和找零由调用者处理，每个输入都有一个空的见证占位
+#[derive(Clone, Debug)]
+pub struct BuiltTx {
+    pub tx: TransactionView,
+    pub cats: Vec<NFTData>,
+}
+
+fn empty_witnesses(count: usize) -> Vec<Bytes> {
+    (0..count).map(|_| Bytes::new()).collect()
+}
+
+fn cat_data(cats: &[NFTData]) -> Vec<Bytes> {
+    cats.iter()
+        .map(|cat| Bytes::from(cat.serialize()))
+        .collect()
+}
+
+//铸造：需要Owner(或者达到阈值的管理员)的输入
+pub struct MintBuilder {
+    deployment: CatDeployment,
+    owner_inputs: Vec<CellInput>,
+    cats: Vec<(Script, u64, NFTData)>,
+}
+
+impl MintBuilder {
+    pub fn new(deployment: CatDeployment) -> Self {
+        MintBuilder {
+            deployment,
+            owner_inputs: Vec::new(),
+            cats: Vec::new(),
+        }
+    }
+
+    pub fn owner_input(mut self, input: CellInput) -> Self {
+        self.owner_inputs.push(input);
+        self
+    }
+
+    //铸造一只新猫给lock，hash由名字和lock hash决定
+    pub fn cat(mut self, name: &str, lock: Script, capacity: u64) -> Result<Self, Error> {
+        let nft = NFTData::mint(name, lock.calc_script_hash().as_slice())?;
+        self.cats.push((lock, capacity, nft));
+        Ok(self)
+    }
+
+    pub fn build(self) -> Result<BuiltTx, Error> {
+        if self.owner_inputs.is_empty() || self.cats.is_empty() {
+            return Err(Error::MissingCell);
+        }
+        let outputs: Vec<CellOutput> = self
+            .cats
+            .iter()
+            .map(|(lock, capacity, _)| self.deployment.cat_output(lock, *capacity))
+            .collect();
+        let cats: Vec<NFTData> = self.cats.into_iter().map(|(_, _, nft)| nft).collect();
+        let tx = self
+            .deployment
+            .builder()
+            .inputs(self.owner_inputs.clone())
+            .outputs(outputs)
+            .outputs_data(cat_data(&cats).pack())
+            .witnesses(empty_witnesses(self.owner_inputs.len()).pack())
+            .build();
+        Ok(BuiltTx { tx, cats })
+    }
+}
+
+//转移：数据不变，只改变lock
+pub struct TransferBuilder {
+    deployment: CatDeployment,
+    transfers: Vec<(CatCell, Script)>,
+}
+
+impl TransferBuilder {
+    pub fn new(deployment: CatDeployment) -> Self {
+        TransferBuilder {
+            deployment,
+            transfers: Vec::new(),
+        }
+    }
+
+    pub fn cat(mut self, cat: CatCell, to: Script) -> Self {
+        self.transfers.push((cat, to));
+        self
+    }
+
+    pub fn build(self) -> Result<BuiltTx, Error> {
+        if self.transfers.is_empty() {
+            return Err(Error::MissingCell);
+        }
+        let inputs: Vec<CellInput> = self
+            .transfers
+            .iter()
+            .map(|(cat, _)| cat.input.clone())
+            .collect();
+        let outputs: Vec<CellOutput> = self
+            .transfers
+            .iter()
+            .map(|(cat, to)| self.deployment.cat_output(to, cat.capacity))
+            .collect();
+        let cats: Vec<NFTData> = self
+            .transfers
+            .into_iter()
+            .map(|(cat, _)| cat.data)
+            .collect();
+        let tx = self
+            .deployment
+            .builder()
+            .inputs(inputs.clone())
+            .outputs(outputs)
+            .outputs_data(cat_data(&cats).pack())
+            .witnesses(empty_witnesses(inputs.len()).pack())
+            .build();
+        Ok(BuiltTx { tx, cats })
+    }
+}
+
+//改名：扣除改名费，必须由主人签名
+pub struct RenameBuilder {
+    deployment: CatDeployment,
+    cat: CatCell,
+    name: String,
+    fee: i32,
+}
+
+impl RenameBuilder {
+    //fee为type script args中的改名费，没有配置时为10
+    pub fn new(deployment: CatDeployment, cat: CatCell, name: &str, fee: i32) -> Self {
+        RenameBuilder {
+            deployment,
+            cat,
+            name: name.to_string(),
+            fee,
+        }
+    }
+
+    pub fn build(self) -> Result<BuiltTx, Error> {
+        let name = encode_name(&self.name)?;
+        if self.fee < 0 || self.cat.data.fishes < self.fee {
+            return Err(Error::NotEnoughFishes);
+        }
+        let output = NFTData {
+            name,
+            fishes: self.cat.data.fishes - self.fee,
+            ..self.cat.data.clone()
+        };
+        let cats = vec![output];
+        let tx = self
+            .deployment
+            .builder()
+            .input(self.cat.input.clone())
+            .output(
+                self.deployment
+                    .cat_output(&self.cat.lock, self.cat.capacity),
+            )
+            .outputs_data(cat_data(&cats).pack())
+            .witnesses(empty_witnesses(1).pack())
+            .build();
+        Ok(BuiltTx { tx, cats })
+    }
+}
+
+//复活：fish恢复到配置的数目，同时支付复活费，必须由主人签名
+pub struct ReviveBuilder {
+    deployment: CatDeployment,
+    cat: CatCell,
+    revive: Revive,
+    fee_lock: Script,
+}
+
+impl ReviveBuilder {
+    //fee_lock是收取复活费的lock：有配置cell时为配置cell的lock，
+    //否则hash必须与type script args中的owner lock hash一致
+    pub fn new(deployment: CatDeployment, cat: CatCell, revive: Revive, fee_lock: Script) -> Self {
+        ReviveBuilder {
+            deployment,
+            cat,
+            revive,
+            fee_lock,
+        }
+    }
+
+    pub fn build(self) -> Result<BuiltTx, Error> {
+        if !self.cat.data.is_knocked_out() {
+            return Err(Error::NotKnockedOut);
+        }
+        let output = NFTData {
+            fishes: self.revive.fishes,
+            ..self.cat.data.clone()
+        };
+        let cats = vec![output];
+        let fee_output = CellOutput::new_builder()
+            .capacity(self.revive.fee.pack())
+            .lock(self.fee_lock.clone())
+            .build();
+        let mut outputs_data = cat_data(&cats);
+        outputs_data.push(Bytes::new());
+        //支付费用的输入由调用者补充
+        let tx = self
+            .deployment
+            .builder()
+            .input(self.cat.input.clone())
+            .output(
+                self.deployment
+                    .cat_output(&self.cat.lock, self.cat.capacity),
+            )
+            .output(fee_output)
+            .outputs_data(outputs_data.pack())
+            .witnesses(empty_witnesses(1).pack())
+            .build();
+        Ok(BuiltTx { tx, cats })
+    }
+}
+
+//战斗：自动计算分出胜负的最小回合数n，写进第二个输入的见证
+pub struct FightBuilder {
+    deployment: CatDeployment,
+    cats: [CatCell; 2],
+    params: Params,
+    season: Season,
+    max_rounds: u16,
+    //通过cell dep使用的装备
+    item_deps: Vec<CellDep>,
+    //作为输入喝掉的药水和喝它的猫，交易中没有对应的输出
+    potion_inputs: Vec<(usize, CellInput)>,
+    //双方带的装备，以及是否作为输入喝掉
+    items: [Vec<(Item, bool)>; 2],
+}
+
+impl FightBuilder {
+    pub fn new(deployment: CatDeployment, challenger: CatCell, defender: CatCell) -> Self {
+        FightBuilder {
+            deployment,
+            cats: [challenger, defender],
+            params: Params::default(),
+            season: Season::default(),
+            max_rounds: u16::MAX,
+            item_deps: Vec::new(),
+            potion_inputs: Vec::new(),
+            items: [Vec::new(), Vec::new()],
+        }
+    }
+
+    pub fn params(mut self, params: Params) -> Self {
+        self.params = params;
+        self
+    }
+
+    pub fn season(mut self, season: Season) -> Self {
+        self.season = season;
+        self
+    }
+
+    pub fn max_rounds(mut self, max_rounds: u16) -> Self {
+        self.max_rounds = max_rounds;
+        self
+    }
+
+    //通过cell dep使用装备，装备的lock必须与第index只猫的lock相同，药水不能这样使用
+    pub fn equip(mut self, index: usize, item_dep: CellDep, item: Item) -> Self {
+        self.item_deps.push(item_dep);
+        self.items[index].push((item, false));
+        self
+    }
+
+    //第index只猫喝掉一瓶药水：药水作为输入并被销毁，它的lock同样必须与猫的lock相同，
+    //药水的capacity由调用者找零。index写进药水的见证，双方是同一个lock时合约靠它分配药水
+    pub fn drink(mut self, index: usize, potion_input: CellInput, potion: Item) -> Self {
+        self.potion_inputs.push((index, potion_input));
+        self.items[index].push((potion, true));
+        self
+    }
+
+    //双方加上装备后的属性，与合约一样每种装备只能带一件，药水必须喝掉
+    fn stats(&self) -> Result<[Statistics; 2], Error> {
+        let mut stats: [Statistics; 2] =
+            [self.cats[0].data.hash.into(), self.cats[1].data.hash.into()];
+        for (stat, items) in stats.iter_mut().zip(self.items.iter()) {
+            for (i, (item, drunk)) in items.iter().enumerate() {
+                if item.is_potion() != *drunk {
+                    return Err(Error::InvalidItem);
+                }
+                if items[..i].iter().any(|(other, _)| other.kind == item.kind) {
+                    return Err(Error::DuplicateItem);
+                }
+                stat.equip(&item.bonus);
+            }
+        }
+        Ok(stats)
+    }
+
+    //只计算结果，不构造交易
+    pub fn predict(&self) -> Result<FightResult, Error> {
+        let lock_hashes = [self.cats[0].lock_hash(), self.cats[1].lock_hash()];
+        let result = fight_with_stats(
+            &[self.cats[0].data.clone(), self.cats[1].data.clone()],
+            [&lock_hashes[0], &lock_hashes[1]],
+            self.stats()?,
+            &self.params,
+            &self.season,
+            self.max_rounds,
+        )?;
+        Ok(result)
+    }
+
+    pub fn build(self) -> Result<(BuiltTx, FightResult), Error> {
+        let result = self.predict()?;
+        let cats = result.outputs.to_vec();
+        let outputs: Vec<CellOutput> = self
+            .cats
+            .iter()
+            .map(|cat| self.deployment.cat_output(&cat.lock, cat.capacity))
+            .collect();
+        //双方的猫必须是输入0和1，药水跟在后面
+        let inputs: Vec<CellInput> = self
+            .cats
+            .iter()
+            .map(|cat| cat.input.clone())
+            .chain(self.potion_inputs.iter().map(|(_, input)| input.clone()))
+            .collect();
+        let mut witnesses = vec![
+            Bytes::new(),
+            WitnessArgs::new_builder()
+                .input_type(Some(Bytes::from(result.rounds.to_be_bytes().to_vec())).pack())
+                .build()
+                .as_bytes(),
+        ];
+        witnesses.extend(self.potion_inputs.iter().map(|(index, _)| {
+            WitnessArgs::new_builder()
+                .input_type(Some(Bytes::from(vec![*index as u8])).pack())
+                .build()
+                .as_bytes()
+        }));
+        let tx = self
+            .deployment
+            .builder()
+            .cell_deps(self.item_deps.clone())
+            .inputs(inputs)
+            .outputs(outputs)
+            .outputs_data(cat_data(&cats).pack())
+            .witnesses(witnesses.pack())
+            .build();
+        Ok((BuiltTx { tx, cats }, result))
+    }
+}
+
+//销毁：猫没有对应的输出，合约只在Owner模式下接受这样的交易，所以必须有Owner的输入：
+//没有配置cell时是lock hash等于args中owner lock hash的cell，有配置cell时是达到阈值的
+//不同管理员的cell(配置cell要在deployment的cell dep中)，否则交易会被合约拒绝
+pub struct BurnBuilder {
+    deployment: CatDeployment,
+    owner_inputs: Vec<CellInput>,
+    cats: Vec<CatCell>,
+}
+
+impl BurnBuilder {
+    pub fn new(deployment: CatDeployment) -> Self {
+        BurnBuilder {
+            deployment,
+            owner_inputs: Vec::new(),
+            cats: Vec::new(),
+        }
+    }
+
+    pub fn owner_input(mut self, input: CellInput) -> Self {
+        self.owner_inputs.push(input);
+        self
+    }
+
+    pub fn cat(mut self, cat: CatCell) -> Self {
+        self.cats.push(cat);
+        self
+    }
+
+    pub fn build(self) -> Result<BuiltTx, Error> {
+        if self.owner_inputs.is_empty() || self.cats.is_empty() {
+            return Err(Error::MissingCell);
+        }
+        let inputs: Vec<CellInput> = self
+            .cats
+            .iter()
+            .map(|cat| cat.input.clone())
+            .chain(self.owner_inputs)
+            .collect();
+        let tx = self
+            .deployment
+            .builder()
+            .inputs(inputs.clone())
+            .witnesses(empty_witnesses(inputs.len()).pack())
+            .build();
+        Ok(BuiltTx {
+            tx,
+            cats: Vec::new(),
+        })
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+
+use sourly_cat_core::error::Error as CoreError;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    //cell data不是合法的猫
+    InvalidData,
+    //配置cell的data不合法
+    InvalidConfig,
+    //名字不合法
+    InvalidName,
+    //fish不够支付改名费
+    NotEnoughFishes,
+    //只有fish为负数的猫才能复活
+    NotKnockedOut,
+    //fish为负数的猫不能战斗
+    KnockedOut,
+    //猫的赛季比当前赛季新
+    WrongSeason,
+    //在最大回合数内分不出胜负
+    Draw,
+    //交易缺少必须的输入或输出
+    MissingCell,
+    //装备cell的data不合法，或者药水没有作为输入
+    InvalidItem,
+    //一只猫带了两件同种类的装备
+    DuplicateItem,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match self {
+            Error::InvalidData => "invalid cat data",
+            Error::InvalidConfig => "invalid config data",
+            Error::InvalidName => "invalid cat name",
+            Error::NotEnoughFishes => "not enough fishes to pay the rename fee",
+            Error::NotKnockedOut => "only knocked out cats can be revived",
+            Error::KnockedOut => "knocked out cats can't fight",
+            Error::WrongSeason => "cat is from a later season than the config",
+            Error::Draw => "no winner within the max rounds",
+            Error::MissingCell => "missing required cells",
+            Error::InvalidItem => "invalid item, potions must be drunk as inputs",
+            Error::DuplicateItem => "a cat can use only one item of each kind",
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<CoreError> for Error {
+    fn from(err: CoreError) -> Self {
+        match err {
+            CoreError::InvalidData | CoreError::InvalidExtension | CoreError::Overflow => {
+                Error::InvalidData
+            }
+            CoreError::InvalidName => Error::InvalidName,
+            CoreError::InvalidConfig => Error::InvalidConfig,
+            CoreError::InvalidItem => Error::InvalidItem,
+            CoreError::WrongSeason => Error::WrongSeason,
+            CoreError::KnockedOut => Error::KnockedOut,
+            CoreError::Draw => Error::Draw,
+        }
+    }
+}
@@ -0,0 +1,19 @@
+//! 链下构造sourly_cat交易的工具
+//!
+//! 猫和配置cell的数据格式以及战斗的计算来自sourly_cat_core，与合约是同一份实现
+//! `builder.rs` 构造未签名的交易
+
+pub mod builder;
+pub mod error;
+
+pub use sourly_cat_core::{battle, config, hash, item, model};
+
+pub use battle::{fight, fight_with_stats, FightResult, Params, Statistics};
+pub use builder::{
+    BuiltTx, BurnBuilder, CatCell, CatDeployment, FightBuilder, MintBuilder, RenameBuilder,
+    ReviveBuilder, TransferBuilder,
+};
+pub use config::{Config, Revive, Season};
+pub use error::Error;
+pub use item::Item;
+pub use model::{Extension, NFTData};
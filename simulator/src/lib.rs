@@ -0,0 +1,3 @@
+//! sourly-cat-sim的模拟部分，单独作为库以便测试
+
+pub mod sim;
@@ -0,0 +1,150 @@
+//! sourly-cat-sim: 用随机的猫模拟战斗，扫描游戏参数并输出平衡性报告
+//!
+//! 战斗计算使用sourly_cat_core，与合约是同一份实现
+
+use std::collections::HashMap;
+use std::fs;
+use std::str::FromStr;
+
+use rand::{rngs::StdRng, SeedableRng};
+use sourly_cat_core::model::DEFAULT_FISHES;
+use sourly_cat_core::Params;
+
+use sourly_cat_simulator::sim::{parse_values, simulate, Report};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+const USAGE: &str = "\
+usage: sourly-cat-sim [options]
+
+  --hp-factor <values>       HP multiplier k (default: 1..10)
+  --def-base <values>        defence constant (default: 250)
+  --winner-divisor <values>  winner fish divisor (default: 10)
+  --loser-divisor <values>   loser fish divisor (default: 10)
+  --samples <n>              fights per parameter set (default: 100000)
+  --fishes <n>               starting fishes of both cats (default: 100)
+  --max-rounds <n>           rounds before a fight counts as a draw (default: 5000)
+  --seed <n>                 random seed, for reproducible reports
+  --format csv|json          report format (default: csv)
+  --out <path>               write the report to a file instead of stdout
+
+<values> is a comma separated list, or an inclusive range like 1..10";
+
+const CSV_HEADER: &str = "hp_factor,def_base,winner_divisor,loser_divisor,samples,\
+challenger_win_rate,defender_win_rate,draw_rate,avg_rounds,fish_inflation,hidden_reward_rate";
+
+fn parse_options() -> Result<HashMap<String, String>> {
+    let mut options = HashMap::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--help" || arg == "-h" {
+            println!("{}", USAGE);
+            std::process::exit(0);
+        }
+        let key = match arg.strip_prefix("--") {
+            Some(key) => key.to_string(),
+            None => return Err(format!("unexpected argument: {}", arg).into()),
+        };
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for --{}", key))?;
+        options.insert(key, value);
+    }
+    Ok(options)
+}
+
+fn get<T: FromStr>(options: &HashMap<String, String>, key: &str, default: T) -> Result<T>
+where
+    <T as FromStr>::Err: std::error::Error + 'static,
+{
+    match options.get(key) {
+        Some(value) => Ok(value.parse()?),
+        None => Ok(default),
+    }
+}
+
+fn to_csv(reports: &[Report]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+    for r in reports.iter() {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{:.6},{:.6},{:.6},{:.3},{:.3},{:.6}\n",
+            r.hp_factor,
+            r.def_base,
+            r.winner_divisor,
+            r.loser_divisor,
+            r.samples,
+            r.challenger_win_rate,
+            r.defender_win_rate,
+            r.draw_rate,
+            r.avg_rounds,
+            r.fish_inflation,
+            r.hidden_reward_rate
+        ));
+    }
+    csv
+}
+
+fn run() -> Result<()> {
+    let options = parse_options()?;
+    let option =
+        |key: &str, default: &'static str| options.get(key).map(|v| v.as_str()).unwrap_or(default);
+    let hp_factors: Vec<u8> = parse_values(option("hp-factor", "1..10"))?;
+    let def_bases: Vec<u16> = parse_values(option("def-base", "250"))?;
+    let winner_divisors: Vec<u8> = parse_values(option("winner-divisor", "10"))?;
+    let loser_divisors: Vec<u8> = parse_values(option("loser-divisor", "10"))?;
+    let samples: u32 = get(&options, "samples", 100_000)?;
+    let fishes: i32 = get(&options, "fishes", DEFAULT_FISHES)?;
+    let max_rounds: u16 = get(&options, "max-rounds", 5000)?;
+    let mut rng = match options.get("seed") {
+        Some(seed) => StdRng::seed_from_u64(seed.parse()?),
+        None => StdRng::from_entropy(),
+    };
+
+    let mut reports = Vec::new();
+    for &hp_factor in hp_factors.iter() {
+        for &def_base in def_bases.iter() {
+            for &winner_divisor in winner_divisors.iter() {
+                for &loser_divisor in loser_divisors.iter() {
+                    let params = Params {
+                        hp_factor,
+                        def_base,
+                        winner_divisor,
+                        loser_divisor,
+                        ..Default::default()
+                    };
+                    //除数为0等不合法的参数在合约中会被拒绝，这里跳过
+                    if params.hp_factor == 0
+                        || params.winner_divisor == 0
+                        || params.loser_divisor == 0
+                    {
+                        eprintln!("skip invalid params: {:?}", params);
+                        continue;
+                    }
+                    match simulate(&mut rng, &params, samples, fishes, max_rounds) {
+                        Ok(report) => reports.push(report),
+                        Err(err) => eprintln!("skip params {:?}: {}", params, err),
+                    }
+                }
+            }
+        }
+    }
+
+    let report = match options.get("format").map(|f| f.as_str()).unwrap_or("csv") {
+        "csv" => to_csv(&reports),
+        "json" => serde_json::to_string_pretty(&reports)?,
+        other => return Err(format!("unknown format: {}", other).into()),
+    };
+    match options.get("out") {
+        Some(path) => fs::write(path, report)?,
+        None => print!("{}", report),
+    }
+    Ok(())
+}
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("error: {}\n\n{}", err, USAGE);
+        std::process::exit(1);
+    }
+}
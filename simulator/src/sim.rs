@@ -0,0 +1,114 @@
+use std::convert::TryFrom;
+
+use rand::Rng;
+use serde::Serialize;
+use sourly_cat_core::battle::fight;
+use sourly_cat_core::model::BADGE_HIDDEN_REWARD;
+use sourly_cat_core::{Error, NFTData, Params, Season};
+
+//一组参数的统计结果
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Report {
+    pub hp_factor: u8,
+    pub def_base: u16,
+    pub winner_divisor: u8,
+    pub loser_divisor: u8,
+    pub samples: u32,
+    //挑战方(输入0)胜利的比例
+    pub challenger_win_rate: f64,
+    pub defender_win_rate: f64,
+    pub draw_rate: f64,
+    //分出胜负的战斗的平均回合数
+    pub avg_rounds: f64,
+    //每场分出胜负的战斗后双方fish总数的平均增加量
+    pub fish_inflation: f64,
+    pub hidden_reward_rate: f64,
+}
+
+//随机生成一只猫，hash决定属性
+pub fn random_cat<R: Rng>(rng: &mut R, fishes: i32) -> (NFTData, [u8; 32]) {
+    let mut hash = [0u8; 20];
+    rng.fill(&mut hash);
+    let mut lock_hash = [0u8; 32];
+    rng.fill(&mut lock_hash);
+    let cat = NFTData {
+        hash,
+        fishes,
+        ..Default::default()
+    };
+    (cat, lock_hash)
+}
+
+//用随机的两只猫战斗samples次
+pub fn simulate<R: Rng>(
+    rng: &mut R,
+    params: &Params,
+    samples: u32,
+    fishes: i32,
+    max_rounds: u16,
+) -> Result<Report, Error> {
+    let season = Season::default();
+    let mut wins = [0u32; 2];
+    let mut draws = 0u32;
+    let mut hidden_rewards = 0u32;
+    let mut rounds = 0u64;
+    let mut inflation = 0i64;
+
+    for _ in 0..samples {
+        let (cat_0, lock_hash_0) = random_cat(rng, fishes);
+        let (cat_1, lock_hash_1) = random_cat(rng, fishes);
+        let inputs = [cat_0, cat_1];
+        match fight(
+            &inputs,
+            [&lock_hash_0, &lock_hash_1],
+            params,
+            &season,
+            max_rounds,
+        ) {
+            Ok(result) => {
+                wins[result.winner] += 1;
+                rounds += result.rounds as u64;
+                let before: i64 = inputs.iter().map(|cat| cat.fishes as i64).sum();
+                let after: i64 = result.outputs.iter().map(|cat| cat.fishes as i64).sum();
+                inflation += after - before;
+                //输入的猫没有徽章，输的一方有这个徽章说明触发了隐藏奖励
+                if result.outputs[1 - result.winner].badges & BADGE_HIDDEN_REWARD != 0 {
+                    hidden_rewards += 1;
+                }
+            }
+            Err(Error::Draw) => draws += 1,
+            Err(err) => return Err(err),
+        }
+    }
+
+    let decided = (wins[0] + wins[1]).max(1) as f64;
+    let samples_f = samples.max(1) as f64;
+    Ok(Report {
+        hp_factor: params.hp_factor,
+        def_base: params.def_base,
+        winner_divisor: params.winner_divisor,
+        loser_divisor: params.loser_divisor,
+        samples,
+        challenger_win_rate: wins[0] as f64 / samples_f,
+        defender_win_rate: wins[1] as f64 / samples_f,
+        draw_rate: draws as f64 / samples_f,
+        avg_rounds: rounds as f64 / decided,
+        fish_inflation: inflation as f64 / decided,
+        hidden_reward_rate: hidden_rewards as f64 / samples_f,
+    })
+}
+
+//"1,2,5" 或 "1..10"，每个值都必须在T的范围内
+pub fn parse_values<T: TryFrom<u64>>(value: &str) -> Result<Vec<T>, Box<dyn std::error::Error>> {
+    let values: Vec<u64> = match value.find("..") {
+        Some(pos) => (value[..pos].trim().parse()?..=value[pos + 2..].trim().parse()?).collect(),
+        None => value
+            .split(',')
+            .map(|v| v.trim().parse())
+            .collect::<Result<_, _>>()?,
+    };
+    values
+        .into_iter()
+        .map(|v| T::try_from(v).map_err(|_| format!("value out of range: {}", v).into()))
+        .collect()
+}
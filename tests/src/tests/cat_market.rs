@@ -0,0 +1,327 @@
+use super::*;
+use ckb_testtool::{builtin::ALWAYS_SUCCESS, context::Context};
+use ckb_tool::ckb_types::{
+    bytes::Bytes,
+    core::{TransactionBuilder, TransactionView},
+    packed::*,
+    prelude::*,
+};
+
+//合约的错误码，与contracts/cat_market/src/error.rs一致
+const ERR_INSUFFICIENT_PAYMENT: i8 = 5;
+const ERR_INVALID_SINCE: i8 = 7;
+
+const PRICE: u64 = 1000;
+
+const AUCTION_START_PRICE: u64 = 2000;
+const AUCTION_END_PRICE: u64 = 1000;
+const AUCTION_START_BLOCK: u64 = 100;
+const AUCTION_END_BLOCK: u64 = 200;
+
+//一口价挂单的定价参数
+fn fixed_pricing() -> Vec<u8> {
+    PRICE.to_be_bytes().to_vec()
+}
+
+//荷兰式拍卖的定价参数
+fn dutch_pricing() -> Vec<u8> {
+    let mut pricing = Vec::with_capacity(32);
+    pricing.extend(AUCTION_START_PRICE.to_be_bytes().iter());
+    pricing.extend(AUCTION_END_PRICE.to_be_bytes().iter());
+    pricing.extend(AUCTION_START_BLOCK.to_be_bytes().iter());
+    pricing.extend(AUCTION_END_BLOCK.to_be_bytes().iter());
+    pricing
+}
+
+fn gen_tx_for_cat_market(
+    context: &mut Context,
+    pricing: &[u8],
+    since: u64,
+    paid: u64,
+    seller_cancel: bool,
+) -> TransactionView {
+    gen_tx_for_cat_market_with(context, pricing, since, paid, seller_cancel, 1, true)
+}
+
+//同一个卖家挂单了listings只猫，一起被买走；pay_seller为false时付款给了别的lock
+fn gen_tx_for_cat_market_with(
+    context: &mut Context,
+    pricing: &[u8],
+    since: u64,
+    paid: u64,
+    seller_cancel: bool,
+    listings: usize,
+    pay_seller: bool,
+) -> TransactionView {
+    //load_script_bin
+    let sourly_cat_out_point = context.deploy_cell(SOURLY_CAT_BIN.clone());
+    let cat_market_out_point = context.deploy_cell(CAT_MARKET_BIN.clone());
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let sourly_cat_dep = CellDep::new_builder()
+        .out_point(sourly_cat_out_point.clone())
+        .build();
+    let cat_market_dep = CellDep::new_builder()
+        .out_point(cat_market_out_point.clone())
+        .build();
+    let always_success_dep = CellDep::new_builder()
+        .out_point(always_success_out_point.clone())
+        .build();
+
+    // prepare scripts
+    let seller_lock = context
+        .build_script(&always_success_out_point, random_20bytes())
+        .expect("lock script");
+    let buyer_lock = context
+        .build_script(&always_success_out_point, random_20bytes())
+        .expect("lock script");
+    let seller_lock_hash = seller_lock.calc_script_hash().as_bytes();
+
+    //args: 卖家 lock hash + 定价参数
+    let mut market_args = Vec::from(&seller_lock_hash[..]);
+    market_args.extend(pricing.iter());
+    let market_lock = context
+        .build_script(&cat_market_out_point, Bytes::from(market_args))
+        .expect("lock script");
+    let type_script = context
+        .build_script(&sourly_cat_out_point, random_20bytes())
+        .expect("script");
+
+    let nft_data: Vec<Bytes> = (0..listings)
+        .map(|_| Bytes::from(NFTData::gen_random_nft(&seller_lock_hash).serialize()))
+        .collect();
+
+    // prepare cells
+    let mut inputs: Vec<CellInput> = nft_data
+        .iter()
+        .map(|data| {
+            let cat_out_point = context.create_cell(
+                CellOutput::new_builder()
+                    .capacity(500u64.pack())
+                    .lock(market_lock.clone())
+                    .type_(Some(type_script.clone()).pack())
+                    .build(),
+                data.clone(),
+            );
+            CellInput::new_builder()
+                .previous_output(cat_out_point)
+                .since(since.pack())
+                .build()
+        })
+        .collect();
+    //撤单时由卖家提供输入，否则由买家付款
+    let payer_lock = if seller_cancel {
+        seller_lock.clone()
+    } else {
+        buyer_lock.clone()
+    };
+    let payer_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(payer_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+    inputs.push(
+        CellInput::new_builder()
+            .previous_output(payer_out_point)
+            .build(),
+    );
+
+    let mut outputs = vec![
+        CellOutput::new_builder()
+            .capacity(500u64.pack())
+            .lock(payer_lock.clone())
+            .type_(Some(type_script.clone()).pack())
+            .build();
+        listings
+    ];
+    let mut outputs_data = nft_data;
+    if paid > 0 {
+        let payee_lock = if pay_seller {
+            seller_lock.clone()
+        } else {
+            context
+                .build_script(&always_success_out_point, random_20bytes())
+                .expect("lock script")
+        };
+        outputs.push(
+            CellOutput::new_builder()
+                .capacity(paid.pack())
+                .lock(payee_lock)
+                .build(),
+        );
+        outputs_data.push(Bytes::new());
+    }
+    outputs.push(
+        CellOutput::new_builder()
+            .capacity((10000u64 - paid).pack())
+            .lock(payer_lock.clone())
+            .build(),
+    );
+    outputs_data.push(Bytes::new());
+
+    let witnesses = vec![Bytes::new(); listings + 1];
+
+    // build transaction
+    let tx = TransactionBuilder::default()
+        .inputs(inputs)
+        .outputs(outputs)
+        .outputs_data(outputs_data.pack())
+        .cell_dep(sourly_cat_dep)
+        .cell_dep(cat_market_dep)
+        .cell_dep(always_success_dep)
+        .witnesses(witnesses.pack())
+        .build();
+    let tx = context.complete_tx(tx);
+    tx
+}
+
+#[test]
+fn test_cat_market_buy() {
+    let mut context = Context::default();
+    let tx = gen_tx_for_cat_market(&mut context, &fixed_pricing(), 0, PRICE, false);
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_cat_market_buy_underpaid() {
+    let mut context = Context::default();
+    let tx = gen_tx_for_cat_market(&mut context, &fixed_pricing(), 0, PRICE - 1, false);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_INSUFFICIENT_PAYMENT);
+}
+
+#[test]
+fn test_cat_market_buy_without_payment() {
+    let mut context = Context::default();
+    let tx = gen_tx_for_cat_market(&mut context, &fixed_pricing(), 0, 0, false);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_INSUFFICIENT_PAYMENT);
+}
+
+#[test]
+fn test_cat_market_pay_wrong_lock() {
+    let mut context = Context::default();
+    //付够了钱，但付给了别的lock
+    let tx = gen_tx_for_cat_market_with(&mut context, &fixed_pricing(), 0, PRICE, false, 1, false);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_INSUFFICIENT_PAYMENT);
+}
+
+#[test]
+fn test_cat_market_buy_multiple_listings() {
+    let mut context = Context::default();
+    let tx =
+        gen_tx_for_cat_market_with(&mut context, &fixed_pricing(), 0, PRICE * 2, false, 2, true);
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+
+    //同一个卖家的两只猫只付了一只的钱
+    let mut context = Context::default();
+    let tx = gen_tx_for_cat_market_with(&mut context, &fixed_pricing(), 0, PRICE, false, 2, true);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_INSUFFICIENT_PAYMENT);
+}
+
+#[test]
+fn test_cat_market_cancel() {
+    let mut context = Context::default();
+    let tx = gen_tx_for_cat_market(&mut context, &fixed_pricing(), 0, 0, true);
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+//按since指定的区块高度付款，并检查少付1 shannon会失败
+fn check_auction_price(since: u64, price: u64) {
+    let mut context = Context::default();
+    let tx = gen_tx_for_cat_market(&mut context, &dutch_pricing(), since, price, false);
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!(
+        "block {}, price {}, consume cycles: {}",
+        since, price, cycles
+    );
+
+    let mut context = Context::default();
+    let tx = gen_tx_for_cat_market(&mut context, &dutch_pricing(), since, price - 1, false);
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_INSUFFICIENT_PAYMENT);
+}
+
+#[test]
+fn test_cat_auction_price_curve() {
+    //没有since时按起拍价
+    check_auction_price(0, AUCTION_START_PRICE);
+    //开始之前
+    check_auction_price(AUCTION_START_BLOCK - 1, AUCTION_START_PRICE);
+    check_auction_price(AUCTION_START_BLOCK, AUCTION_START_PRICE);
+    //线性下降
+    check_auction_price(AUCTION_START_BLOCK + 1, 1990);
+    check_auction_price(150, 1500);
+    check_auction_price(AUCTION_END_BLOCK - 1, 1010);
+    //结束之后保持底价
+    check_auction_price(AUCTION_END_BLOCK, AUCTION_END_PRICE);
+    check_auction_price(AUCTION_END_BLOCK + 1000, AUCTION_END_PRICE);
+}
+
+#[test]
+fn test_cat_auction_relative_since() {
+    let mut context = Context::default();
+    //相对区块高度的since不能用来定价
+    let since = 0x8000_0000_0000_0000u64 | 150;
+    let tx = gen_tx_for_cat_market(&mut context, &dutch_pricing(), since, PRICE, false);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_INVALID_SINCE);
+}
+
+#[test]
+fn test_cat_auction_floor_at_end_block() {
+    //到结束区块时价格正好降到底价
+    let mut context = Context::default();
+    let tx = gen_tx_for_cat_market(
+        &mut context,
+        &dutch_pricing(),
+        AUCTION_END_BLOCK,
+        AUCTION_END_PRICE,
+        false,
+    );
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+
+    //since早了一个区块，价格还没降到底价
+    let mut context = Context::default();
+    let tx = gen_tx_for_cat_market(
+        &mut context,
+        &dutch_pricing(),
+        AUCTION_END_BLOCK - 1,
+        AUCTION_END_PRICE,
+        false,
+    );
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_INSUFFICIENT_PAYMENT);
+}
+
+#[test]
+fn test_cat_auction_cancel() {
+    let mut context = Context::default();
+    let tx = gen_tx_for_cat_market(&mut context, &dutch_pricing(), 0, 0, true);
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
@@ -0,0 +1,462 @@
+use super::*;
+use ckb_testtool::context::Context;
+use ckb_tool::ckb_types::{
+    bytes::Bytes,
+    core::{TransactionBuilder, TransactionView},
+    packed::*,
+    prelude::*,
+};
+
+const RENTAL_END_BLOCK: u64 = 1000;
+
+//合约的错误码，与contracts/cat_rental/src/error.rs一致
+const ERR_UNAUTHORIZED: i8 = 5;
+const ERR_RENTAL_NOT_EXPIRED: i8 = 6;
+const ERR_INVALID_SINCE: i8 = 7;
+const ERR_CAT_NOT_RETURNED: i8 = 8;
+const ERR_RENTAL_EXPIRED: i8 = 9;
+const ERR_NOT_FIGHT: i8 = 10;
+
+struct RentalEnv {
+    cat: CatEnv,
+    cat_rental_out_point: OutPoint,
+    owner_lock: Script,
+    borrower_lock: Script,
+    rental_lock: Script,
+}
+
+impl RentalEnv {
+    //配置cell中登记了租借lock
+    fn new(context: &mut Context) -> Self {
+        Self::build(context, true)
+    }
+
+    fn build(context: &mut Context, registered: bool) -> Self {
+        //load_script_bin
+        let cat_rental_out_point = context.deploy_cell(CAT_RENTAL_BIN.clone());
+        let rental_code_hash = CellOutput::calc_data_hash(&CAT_RENTAL_BIN);
+        let config = Config {
+            threshold: 1,
+            //测试中不需要管理员签名
+            admin_lock_hashes: vec![vec![0u8; 32]],
+            rental_code_hash: if registered {
+                Some(rental_code_hash.as_slice().to_vec())
+            } else {
+                None
+            },
+            ..Default::default()
+        };
+        let cat = CatEnv::new(context, &config);
+
+        // prepare scripts
+        let owner_lock = cat.gen_lock(context);
+        let borrower_lock = cat.gen_lock(context);
+
+        //args: 主人 lock hash + 借用者 lock hash + 到期区块
+        let mut rental_args = Vec::with_capacity(72);
+        rental_args.extend(owner_lock.calc_script_hash().as_slice().iter());
+        rental_args.extend(borrower_lock.calc_script_hash().as_slice().iter());
+        rental_args.extend(RENTAL_END_BLOCK.to_be_bytes().iter());
+        let rental_lock = context
+            .build_script(&cat_rental_out_point, Bytes::from(rental_args))
+            .expect("lock script");
+
+        RentalEnv {
+            cat,
+            cat_rental_out_point,
+            owner_lock,
+            borrower_lock,
+            rental_lock,
+        }
+    }
+
+    fn cell_deps(&self) -> Vec<CellDep> {
+        let mut cell_deps = self.cat.cell_deps.clone();
+        cell_deps.push(
+            CellDep::new_builder()
+                .out_point(self.cat_rental_out_point.clone())
+                .build(),
+        );
+        cell_deps
+    }
+
+    fn create_cat(
+        &self,
+        context: &mut Context,
+        lock: &Script,
+        nft: &NFTData,
+        since: u64,
+    ) -> CellInput {
+        let out_point = context.create_cell(
+            self.cat.cat_output(lock),
+            Bytes::from(nft.serialize().to_vec()),
+        );
+        CellInput::new_builder()
+            .previous_output(out_point)
+            .since(since.pack())
+            .build()
+    }
+
+    //用于授权的普通cell
+    fn auth_input(&self, context: &mut Context, lock: &Script) -> CellInput {
+        let out_point = context.create_cell(auth_output(lock), Bytes::new());
+        CellInput::new_builder().previous_output(out_point).build()
+    }
+
+    fn build_tx(
+        &self,
+        context: &mut Context,
+        inputs: Vec<CellInput>,
+        outputs: Vec<CellOutput>,
+        outputs_data: Vec<Bytes>,
+        witnesses: Vec<Bytes>,
+    ) -> TransactionView {
+        let tx = TransactionBuilder::default()
+            .inputs(inputs)
+            .outputs(outputs)
+            .outputs_data(outputs_data.pack())
+            .cell_deps(self.cell_deps())
+            .witnesses(witnesses.pack())
+            .build();
+        context.complete_tx(tx)
+    }
+}
+
+fn auth_output(lock: &Script) -> CellOutput {
+    CellOutput::new_builder()
+        .capacity(1000u64.pack())
+        .lock(lock.clone())
+        .build()
+}
+
+fn fight_witness(n: u16) -> Bytes {
+    WitnessArgsBuilder::default()
+        .input_type(Some(Bytes::from(n.to_be_bytes().to_vec())).pack())
+        .build()
+        .as_bytes()
+}
+
+//租来的猫作为挑战方去战斗，authorizer提供授权输入，战斗后猫回到cat_output_lock下
+fn gen_tx_for_rental_fight(
+    context: &mut Context,
+    env: &RentalEnv,
+    authorizer: &Script,
+    cat_output_lock: &Script,
+    since: u64,
+) -> TransactionView {
+    let opponent_lock = env.cat.gen_lock(context);
+    let lock_hash_0 = Vec::from(opponent_lock.calc_script_hash().as_slice());
+    let lock_hash_1 = Vec::from(env.rental_lock.calc_script_hash().as_slice());
+
+    //平局的话重新生成
+    let (input_nft, (n, output_nft)) = loop {
+        let input_nft = vec![
+            NFTData::gen_random_nft(&lock_hash_0),
+            NFTData::gen_random_nft(&lock_hash_1),
+        ];
+        if let Some(result) = gen_fight_result(
+            &input_nft,
+            &lock_hash_0,
+            &lock_hash_1,
+            3000,
+            &env.cat.params,
+        ) {
+            break (input_nft, result);
+        }
+    };
+
+    let inputs = vec![
+        env.create_cat(context, &opponent_lock, &input_nft[0], 0),
+        env.create_cat(context, &env.rental_lock, &input_nft[1], since),
+        env.auth_input(context, authorizer),
+    ];
+    let outputs = vec![
+        env.cat.cat_output(&opponent_lock),
+        env.cat.cat_output(cat_output_lock),
+        auth_output(authorizer),
+    ];
+    let outputs_data = vec![
+        Bytes::from(output_nft[0].serialize().to_vec()),
+        Bytes::from(output_nft[1].serialize().to_vec()),
+        Bytes::new(),
+    ];
+    let witnesses = vec![Bytes::new(), fight_witness(n), Bytes::new()];
+    env.build_tx(context, inputs, outputs, outputs_data, witnesses)
+}
+
+//主人在since指定的区块收回租出去的猫
+fn gen_tx_for_rental_reclaim(
+    context: &mut Context,
+    env: &RentalEnv,
+    since: u64,
+) -> TransactionView {
+    let owner_hash = Vec::from(env.owner_lock.calc_script_hash().as_slice());
+    let nft = NFTData::gen_random_nft(&owner_hash);
+
+    let inputs = vec![
+        env.create_cat(context, &env.rental_lock, &nft, since),
+        env.auth_input(context, &env.owner_lock),
+    ];
+    let outputs = vec![
+        env.cat.cat_output(&env.owner_lock),
+        auth_output(&env.owner_lock),
+    ];
+    let outputs_data = vec![Bytes::from(nft.serialize().to_vec()), Bytes::new()];
+    let witnesses = vec![Bytes::new(), Bytes::new()];
+    env.build_tx(context, inputs, outputs, outputs_data, witnesses)
+}
+
+//authorizer给租借lock下的猫改名，猫仍然留在租借lock下，见证中带有签名
+fn gen_tx_for_rental_rename(
+    context: &mut Context,
+    env: &RentalEnv,
+    authorizer: &Script,
+    since: u64,
+) -> TransactionView {
+    let rental_hash = Vec::from(env.rental_lock.calc_script_hash().as_slice());
+    let input_nft = NFTData::gen_random_nft(&rental_hash);
+    let mut output_nft = input_nft.clone();
+    output_nft.name = [0u8; 16];
+    output_nft.name[..3].copy_from_slice(b"Tom");
+    output_nft.fishes -= DEFAULT_RENAME_FEE;
+
+    let inputs = vec![
+        env.create_cat(context, &env.rental_lock, &input_nft, since),
+        env.auth_input(context, authorizer),
+    ];
+    let outputs = vec![
+        env.cat.cat_output(&env.rental_lock),
+        auth_output(authorizer),
+    ];
+    let outputs_data = vec![Bytes::from(output_nft.serialize().to_vec()), Bytes::new()];
+    let witnesses = vec![
+        WitnessArgsBuilder::default()
+            .lock(Some(Bytes::from(vec![0u8; SIGNATURE_SIZE])).pack())
+            .build()
+            .as_bytes(),
+        Bytes::new(),
+    ];
+    env.build_tx(context, inputs, outputs, outputs_data, witnesses)
+}
+
+#[test]
+fn test_cat_rental_fight() {
+    let mut context = Context::default();
+    let env = RentalEnv::new(&mut context);
+    let tx = gen_tx_for_rental_fight(
+        &mut context,
+        &env,
+        &env.borrower_lock,
+        &env.rental_lock,
+        RENTAL_END_BLOCK - 1,
+    );
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_cat_rental_fight_without_borrower() {
+    let mut context = Context::default();
+    let env = RentalEnv::new(&mut context);
+    let stranger = env.cat.gen_lock(&mut context);
+    let tx = gen_tx_for_rental_fight(
+        &mut context,
+        &env,
+        &stranger,
+        &env.rental_lock,
+        RENTAL_END_BLOCK - 1,
+    );
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_UNAUTHORIZED);
+}
+
+#[test]
+fn test_cat_rental_borrower_takes_cat() {
+    let mut context = Context::default();
+    let env = RentalEnv::new(&mut context);
+    //战斗后猫的lock变成了借用者的
+    let tx = gen_tx_for_rental_fight(
+        &mut context,
+        &env,
+        &env.borrower_lock,
+        &env.borrower_lock,
+        RENTAL_END_BLOCK - 1,
+    );
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_CAT_NOT_RETURNED);
+}
+
+#[test]
+fn test_cat_rental_fight_after_end() {
+    let mut context = Context::default();
+    let env = RentalEnv::new(&mut context);
+    let tx = gen_tx_for_rental_fight(
+        &mut context,
+        &env,
+        &env.borrower_lock,
+        &env.rental_lock,
+        RENTAL_END_BLOCK,
+    );
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_RENTAL_EXPIRED);
+}
+
+#[test]
+fn test_cat_rental_fight_without_since() {
+    let mut context = Context::default();
+    let env = RentalEnv::new(&mut context);
+    let tx = gen_tx_for_rental_fight(&mut context, &env, &env.borrower_lock, &env.rental_lock, 0);
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_INVALID_SINCE);
+
+    //相对区块高度的since不能证明租期
+    let since = 0x8000_0000_0000_0000u64 | 1;
+    let tx = gen_tx_for_rental_fight(
+        &mut context,
+        &env,
+        &env.borrower_lock,
+        &env.rental_lock,
+        since,
+    );
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_INVALID_SINCE);
+}
+
+#[test]
+fn test_cat_rental_swap_cat() {
+    let mut context = Context::default();
+    let env = RentalEnv::new(&mut context);
+    let rental_hash = Vec::from(env.rental_lock.calc_script_hash().as_slice());
+    let borrower_hash = Vec::from(env.borrower_lock.calc_script_hash().as_slice());
+    let rented = NFTData::gen_random_nft(&rental_hash);
+    //借用者自己的猫，名字和租来的猫相同
+    let mut own = NFTData::gen_random_nft(&borrower_hash);
+    own.name = rented.name;
+
+    //借用者的猫放到租借lock下，租来的猫转给借用者，每一对数据都没变
+    let inputs = vec![
+        env.create_cat(
+            &mut context,
+            &env.rental_lock,
+            &rented,
+            RENTAL_END_BLOCK - 1,
+        ),
+        env.create_cat(&mut context, &env.borrower_lock, &own, 0),
+    ];
+    let outputs = vec![
+        env.cat.cat_output(&env.borrower_lock),
+        env.cat.cat_output(&env.rental_lock),
+    ];
+    let outputs_data = vec![
+        Bytes::from(rented.serialize().to_vec()),
+        Bytes::from(own.serialize().to_vec()),
+    ];
+    let witnesses = vec![Bytes::new(), fight_witness(1)];
+    let tx = env.build_tx(&mut context, inputs, outputs, outputs_data, witnesses);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_CAT_NOT_RETURNED);
+}
+
+#[test]
+fn test_cat_rental_borrower_rename() {
+    let mut context = Context::default();
+    let env = RentalEnv::new(&mut context);
+    //借用者只能拿猫去战斗
+    let tx = gen_tx_for_rental_rename(&mut context, &env, &env.borrower_lock, RENTAL_END_BLOCK - 1);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_NOT_FIGHT);
+}
+
+#[test]
+fn test_cat_rental_borrower_rename_with_third_cat() {
+    //配置中没有登记租借lock，借用者再放进一只自己的猫，交易就不再是sourly_cat的战斗，
+    //租借lock下的见证会被当作主人的签名来改名
+    let mut context = Context::default();
+    let env = RentalEnv::build(&mut context, false);
+    let rental_hash = Vec::from(env.rental_lock.calc_script_hash().as_slice());
+    let borrower_hash = Vec::from(env.borrower_lock.calc_script_hash().as_slice());
+    let rented = NFTData::gen_random_nft(&rental_hash);
+    let mut renamed = rented.clone();
+    renamed.name = [0u8; 16];
+    renamed.name[..3].copy_from_slice(b"Tom");
+    renamed.fishes -= DEFAULT_RENAME_FEE;
+    let own = [
+        NFTData::gen_random_nft(&borrower_hash),
+        NFTData::gen_random_nft(&borrower_hash),
+    ];
+
+    let inputs = vec![
+        env.create_cat(
+            &mut context,
+            &env.rental_lock,
+            &rented,
+            RENTAL_END_BLOCK - 1,
+        ),
+        env.create_cat(&mut context, &env.borrower_lock, &own[0], 0),
+        env.create_cat(&mut context, &env.borrower_lock, &own[1], 0),
+    ];
+    let outputs = vec![
+        env.cat.cat_output(&env.rental_lock),
+        env.cat.cat_output(&env.borrower_lock),
+        env.cat.cat_output(&env.borrower_lock),
+    ];
+    let outputs_data = vec![
+        Bytes::from(renamed.serialize().to_vec()),
+        Bytes::from(own[0].serialize().to_vec()),
+        Bytes::from(own[1].serialize().to_vec()),
+    ];
+    let witnesses = vec![
+        WitnessArgsBuilder::default()
+            .lock(Some(Bytes::from(vec![0u8; SIGNATURE_SIZE])).pack())
+            .build()
+            .as_bytes(),
+        fight_witness(1),
+        Bytes::new(),
+    ];
+    let tx = env.build_tx(&mut context, inputs, outputs, outputs_data, witnesses);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_NOT_FIGHT);
+}
+
+#[test]
+fn test_cat_rental_rename_under_rental_lock() {
+    //配置中没有登记租借lock时，租借lock下的见证就能当作主人的签名
+    let mut context = Context::default();
+    let env = RentalEnv::build(&mut context, false);
+    let tx = gen_tx_for_rental_rename(&mut context, &env, &env.owner_lock, RENTAL_END_BLOCK);
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+
+    //登记之后，必须先收回再改名
+    let mut context = Context::default();
+    let env = RentalEnv::new(&mut context);
+    let tx = gen_tx_for_rental_rename(&mut context, &env, &env.owner_lock, RENTAL_END_BLOCK);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_RENAME);
+}
+
+#[test]
+fn test_cat_rental_reclaim() {
+    let mut context = Context::default();
+    let env = RentalEnv::new(&mut context);
+    let tx = gen_tx_for_rental_reclaim(&mut context, &env, RENTAL_END_BLOCK);
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_cat_rental_reclaim_before_end() {
+    let mut context = Context::default();
+    let env = RentalEnv::new(&mut context);
+    let tx = gen_tx_for_rental_reclaim(&mut context, &env, RENTAL_END_BLOCK - 1);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_RENTAL_NOT_EXPIRED);
+}
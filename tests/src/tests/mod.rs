@@ -1,15 +1,37 @@
+mod cat_market;
+mod cat_rental;
 mod hash;
+mod nft_admin;
+mod nft_badge;
 mod nft_create;
+mod nft_cycles;
+mod nft_errors;
+mod nft_extension;
 mod nft_fighting;
+mod nft_item;
+mod nft_lineage;
+mod nft_native;
+mod nft_pause;
+mod nft_proptest;
+mod nft_record;
+mod nft_rename;
+mod nft_revive;
+mod nft_season;
 mod nft_transfer;
+mod nft_vectors;
+mod sdk_builder;
+mod simulator;
 
 use ckb_crypto::secp::Privkey;
 use ckb_fixed_hash::H256;
+use ckb_testtool::builtin::ALWAYS_SUCCESS;
 use ckb_testtool::context::Context;
 use ckb_tool::ckb_types::{
     bytes::Bytes,
-    core::TransactionView,
-    packed::{self, WitnessArgs},
+    core::{TransactionBuilder, TransactionView},
+    packed::{
+        self, CellDep, CellInput, CellOutput, OutPoint, Script, WitnessArgs, WitnessArgsBuilder,
+    },
     prelude::*,
 };
 
@@ -17,6 +39,9 @@ use lazy_static::lazy_static;
 use openssl::base64;
 use rand::{thread_rng, Rng};
 use sha2::{Digest as SHA2Digest, Sha256};
+use sourly_cat_core::battle;
+use sourly_cat_core::model::{Extension, NFTData as CoreNFTData};
+use sourly_cat_core::Error as CoreError;
 use std::{env, fs, path::PathBuf, str::FromStr};
 
 pub const SIGNATURE_SIZE: usize = 65;
@@ -28,12 +53,50 @@ pub const CHAIN_ID_DOGE: u8 = 5;
 
 use sha3::{Digest, Keccak256};
 
-use self::hash::blake2b_160;
+use self::hash::{blake2b_160, eth160, pubkey_compressed, pubkey_uncompressed, ripemd_sha};
 
 const MAX_CYCLES: u64 = 100_000_000;
 
+//战斗规则、配置格式和常量都来自合约使用的核心库
+pub use sourly_cat_core::battle::{Params, Statistics};
+pub use sourly_cat_core::config::{Revive, Season};
+pub use sourly_cat_core::item::{
+    ITEM_KIND_ARMOUR, ITEM_KIND_CLAWS, ITEM_KIND_COLLAR, ITEM_KIND_POTION,
+};
+pub use sourly_cat_core::model::{
+    BADGE_FIRST_WIN, BADGE_HIDDEN_REWARD, BADGE_NEGATIVE_SURVIVOR, BADGE_WIN_STREAK,
+    EXTENSION_ATTRIBUTES, EXTENSION_DESCRIPTION, EXTENSION_IMAGE,
+};
+
+//与合约中的默认改名费一致
+pub const DEFAULT_RENAME_FEE: i32 = 10;
+
+//合约的错误码，与contracts/sourly_cat/src/error.rs一致
+pub const ERR_ENCODING: i8 = 4;
+pub const ERR_WRONG_INPUT_OUTPUT: i8 = 5;
+pub const ERR_UNMATCH_PLAYER: i8 = 6;
+pub const ERR_WRONG_RESULT: i8 = 7;
+pub const ERR_WRONG_TRANSFER: i8 = 8;
+pub const ERR_WRONG_RENAME: i8 = 9;
+pub const ERR_INVALID_NAME: i8 = 10;
+pub const ERR_CONFIG_MISSING: i8 = 11;
+pub const ERR_INVALID_CONFIG: i8 = 12;
+pub const ERR_PAUSED: i8 = 13;
+pub const ERR_WRONG_SEASON: i8 = 14;
+pub const ERR_KNOCKED_OUT: i8 = 15;
+pub const ERR_WRONG_REVIVE: i8 = 16;
+pub const ERR_INVALID_ITEM: i8 = 17;
+pub const ERR_DUPLICATE_ITEM: i8 = 18;
+pub const ERR_POTION_NOT_BURNED: i8 = 19;
+pub const ERR_INVALID_EXTENSION: i8 = 20;
+pub const ERR_WRONG_EXTENSION: i8 = 21;
+pub const ERR_UNASSIGNED_POTION: i8 = 22;
+
 lazy_static! {
     pub static ref SOURLY_CAT_BIN: Bytes = Loader::default().load_binary("sourly_cat");
+    pub static ref CAT_MARKET_BIN: Bytes = Loader::default().load_binary("cat_market");
+    pub static ref CAT_RENTAL_BIN: Bytes = Loader::default().load_binary("cat_rental");
+    pub static ref CAT_ITEM_BIN: Bytes = Loader::default().load_binary("cat_item");
     pub static ref SECP256K1_DATA_BIN: Bytes =
         Bytes::from(&include_bytes!("../../../pw-lock/specs/cells/secp256k1_data")[..]);
     pub static ref KECCAK256_ALL_ACPL_BIN: Bytes =
@@ -98,6 +161,16 @@ pub struct NFTData {
     name: [u8; 16],
     hash: [u8; 20],
     fishes: i32,
+    season: u32,
+    last_season_fishes: i32,
+    badges: u16,
+    win_streak: u8,
+    wins: u32,
+    losses: u32,
+    generation: u32,
+    prev_hash: [u8; 20],
+    //扩展记录：类型和内容，按类型递增排列
+    extensions: Vec<(u8, Vec<u8>)>,
 }
 
 impl NFTData {
@@ -106,17 +179,78 @@ impl NFTData {
             name: [0u8; 16],
             hash: [0u8; 20],
             fishes: 0,
+            season: 0,
+            last_season_fishes: 0,
+            badges: 0,
+            win_streak: 0,
+            wins: 0,
+            losses: 0,
+            generation: 0,
+            prev_hash: [0u8; 20],
+            extensions: Vec::new(),
         };
     }
 
-    fn serialize(&self) -> [u8; 40] {
-        let mut buf = [0u8; 40];
+    fn serialize(&self) -> Vec<u8> {
+        let mut buf = vec![0u8; 83];
         buf[0..16].copy_from_slice(&self.name);
         buf[16..36].copy_from_slice(&self.hash);
         buf[36..40].copy_from_slice(&self.fishes.to_be_bytes());
+        buf[40..44].copy_from_slice(&self.season.to_be_bytes());
+        buf[44..48].copy_from_slice(&self.last_season_fishes.to_be_bytes());
+        buf[48..50].copy_from_slice(&self.badges.to_be_bytes());
+        buf[50] = self.win_streak;
+        buf[51..55].copy_from_slice(&self.wins.to_be_bytes());
+        buf[55..59].copy_from_slice(&self.losses.to_be_bytes());
+        buf[59..63].copy_from_slice(&self.generation.to_be_bytes());
+        buf[63..83].copy_from_slice(&self.prev_hash);
+        for (kind, value) in self.extensions.iter() {
+            buf.push(*kind);
+            buf.extend((value.len() as u16).to_be_bytes().iter());
+            buf.extend(value.iter());
+        }
         return buf;
     }
 
+    //没有赛季字段的旧格式，合约视为第0赛季
+    fn serialize_legacy(&self) -> [u8; 40] {
+        let mut buf = [0u8; 40];
+        buf.copy_from_slice(&self.serialize()[0..40]);
+        return buf;
+    }
+
+    //按核心库的规则迁移到当前赛季，未来赛季的猫保持不变，由合约拒绝
+    fn migrate(&self, season: &Season) -> NFTData {
+        match self.to_core().migrate(season) {
+            Ok(nft) => NFTData::from(&nft),
+            Err(_) => self.clone(),
+        }
+    }
+
+    fn to_core(&self) -> CoreNFTData {
+        CoreNFTData {
+            name: self.name,
+            hash: self.hash,
+            fishes: self.fishes,
+            season: self.season,
+            last_season_fishes: self.last_season_fishes,
+            badges: self.badges,
+            win_streak: self.win_streak,
+            wins: self.wins,
+            losses: self.losses,
+            generation: self.generation,
+            prev_hash: self.prev_hash,
+            extensions: self
+                .extensions
+                .iter()
+                .map(|(kind, value)| Extension {
+                    kind: *kind,
+                    value: value.clone(),
+                })
+                .collect(),
+        }
+    }
+
     fn gen_random_nft(lock_hash: &[u8]) -> NFTData {
         let mut nft = NFTData::new();
 
@@ -149,40 +283,417 @@ impl From<&[u8]> for NFTData {
         fishes_byte.copy_from_slice(&data[36..40]);
         //采用大端序
         let fishes = i32::from_be_bytes(fishes_byte);
+        let mut season = [0u8; 4];
+        let mut last_season_fishes = [0u8; 4];
+        if data.len() >= 48 {
+            season.copy_from_slice(&data[40..44]);
+            last_season_fishes.copy_from_slice(&data[44..48]);
+        }
+        let mut badges = [0u8; 2];
+        let mut win_streak = 0;
+        if data.len() >= 51 {
+            badges.copy_from_slice(&data[48..50]);
+            win_streak = data[50];
+        }
+        let mut wins = [0u8; 4];
+        let mut losses = [0u8; 4];
+        if data.len() >= 59 {
+            wins.copy_from_slice(&data[51..55]);
+            losses.copy_from_slice(&data[55..59]);
+        }
+        let mut generation = [0u8; 4];
+        let mut prev_hash = [0u8; 20];
+        if data.len() >= 83 {
+            generation.copy_from_slice(&data[59..63]);
+            prev_hash.copy_from_slice(&data[63..83]);
+        }
         NFTData {
             name: name,
             hash: hash,
             fishes: fishes,
+            season: u32::from_be_bytes(season),
+            last_season_fishes: i32::from_be_bytes(last_season_fishes),
+            badges: u16::from_be_bytes(badges),
+            win_streak: win_streak,
+            wins: u32::from_be_bytes(wins),
+            losses: u32::from_be_bytes(losses),
+            generation: u32::from_be_bytes(generation),
+            prev_hash: prev_hash,
+            extensions: Vec::new(),
+        }
+    }
+}
+
+impl From<&CoreNFTData> for NFTData {
+    fn from(nft: &CoreNFTData) -> Self {
+        NFTData {
+            name: nft.name,
+            hash: nft.hash,
+            fishes: nft.fishes,
+            season: nft.season,
+            last_season_fishes: nft.last_season_fishes,
+            badges: nft.badges,
+            win_streak: nft.win_streak,
+            wins: nft.wins,
+            losses: nft.losses,
+            generation: nft.generation,
+            prev_hash: nft.prev_hash,
+            extensions: nft
+                .extensions
+                .iter()
+                .map(|extension| (extension.kind, extension.value.clone()))
+                .collect(),
         }
     }
 }
 
 #[derive(Debug)]
-pub struct Statistics {
-    hp: u8,
-    atk: u8,
-    def: u8,
-    lck: u8,
+pub struct Item {
+    kind: u8,
+    bonus: Statistics,
+}
+
+impl Item {
+    fn serialize(&self) -> Vec<u8> {
+        let bonus = &self.bonus;
+        vec![self.kind, bonus.hp, bonus.atk, bonus.def, bonus.lck]
+    }
+}
+
+//按照合约的规则计算战斗结果，返回最小的战斗回合数和双方战斗后的NFT，平局时返回None
+pub fn gen_fight_result(
+    input_nft: &[NFTData],
+    lock_hash_0: &[u8],
+    lock_hash_1: &[u8],
+    max_fight_count: u16,
+    params: &Params,
+) -> Option<(u16, Vec<NFTData>)> {
+    //计算双方的挑战前属性值
+    let stats = [input_nft[0].hash.into(), input_nft[1].hash.into()];
+    gen_fight_result_with_stats(
+        input_nft,
+        lock_hash_0,
+        lock_hash_1,
+        max_fight_count,
+        params,
+        stats,
+    )
+}
+
+//同上，双方的属性值由调用者给出，例如加上了装备；规则来自合约使用的核心库
+pub fn gen_fight_result_with_stats(
+    input_nft: &[NFTData],
+    lock_hash_0: &[u8],
+    lock_hash_1: &[u8],
+    max_fight_count: u16,
+    params: &Params,
+    stats: [Statistics; 2],
+) -> Option<(u16, Vec<NFTData>)> {
+    let inputs = [input_nft[0].to_core(), input_nft[1].to_core()];
+    let lock_hashes = [lock_hash_0, lock_hash_1];
+    match battle::resolve(&inputs, lock_hashes, &stats, params, max_fight_count) {
+        Ok(result) => Some((
+            result.rounds,
+            result.outputs.iter().map(NFTData::from).collect(),
+        )),
+        Err(CoreError::Draw) => None,
+        Err(err) => panic!("fight result: {}", err),
+    }
+}
+
+//配置cell，与合约中的格式一致
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    threshold: u8,
+    admin_lock_hashes: Vec<Vec<u8>>,
+    flags: u8,
+    params: Option<Params>,
+    season: Option<Season>,
+    revive: Option<Revive>,
+    item_type_hash: Option<Vec<u8>>,
+    rental_code_hash: Option<Vec<u8>>,
 }
 
-impl From<[u8; 20]> for Statistics {
-    fn from(hash: [u8; 20]) -> Self {
-        //只看每五个字节的最后一个字节
-        let hp = hash[4] % 100 + 1;
-        let atk = hash[9] % 100 + 1;
-        let def = hash[14] % 100 + 1;
-        let lck = hash[19] % 100 + 1;
-        return Statistics { hp, atk, def, lck };
+pub const CONFIG_FLAG_PAUSED: u8 = 0x01;
+
+impl Config {
+    fn serialize(&self) -> Vec<u8> {
+        let mut buf = vec![self.threshold, self.admin_lock_hashes.len() as u8];
+        for lock_hash in self.admin_lock_hashes.iter() {
+            buf.extend(lock_hash.iter());
+        }
+        buf.push(self.flags);
+        //后面的部分存在时，前面的部分必须写上
+        let rental = self.rental_code_hash.is_some();
+        let item = self.item_type_hash.is_some() || rental;
+        if self.params.is_some() || self.season.is_some() || self.revive.is_some() || item {
+            buf.extend(self.params().serialize());
+        }
+        if self.season.is_some() || self.revive.is_some() || item {
+            buf.extend(self.season().serialize());
+        }
+        if self.revive.is_some() || item {
+            buf.extend(self.revive().serialize());
+        }
+        if item {
+            //全0表示不使用装备
+            let item_type_hash = self.item_type_hash.clone().unwrap_or_else(|| vec![0u8; 32]);
+            buf.extend(item_type_hash.iter());
+        }
+        if let Some(rental_code_hash) = &self.rental_code_hash {
+            buf.extend(rental_code_hash.iter());
+        }
+        buf
+    }
+
+    fn params(&self) -> Params {
+        self.params.clone().unwrap_or_default()
+    }
+
+    fn season(&self) -> Season {
+        self.season.clone().unwrap_or_default()
+    }
+
+    fn revive(&self) -> Revive {
+        self.revive.clone().unwrap_or_default()
     }
 }
 
-impl Statistics {
-    fn to_bytes(&self) -> Vec<u8> {
-        let a = vec![self.hp, self.atk, self.def, self.lck];
-        a
+//使用always success lock的测试环境，合约args引用配置cell
+pub struct CatEnv {
+    params: Params,
+    season: Season,
+    revive: Revive,
+    //args中的Owner，也是配置cell的lock
+    owner_lock: Script,
+    //装备的type script，Owner同上
+    item_type: Script,
+    always_success_out_point: OutPoint,
+    type_script: Script,
+    cell_deps: Vec<CellDep>,
+}
+
+impl CatEnv {
+    pub fn new(context: &mut Context, config: &Config) -> Self {
+        Self::build(context, config.clone(), false)
+    }
+
+    //配置cell中启用装备
+    pub fn with_items(context: &mut Context, config: &Config) -> Self {
+        Self::build(context, config.clone(), true)
+    }
+
+    fn build(context: &mut Context, mut config: Config, items: bool) -> Self {
+        let sourly_cat_out_point = context.deploy_cell(SOURLY_CAT_BIN.clone());
+        let cat_item_out_point = context.deploy_cell(CAT_ITEM_BIN.clone());
+        let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+
+        //用always success代替type id，只要type hash唯一即可
+        let config_type = context
+            .build_script(&always_success_out_point, random_20bytes())
+            .expect("script");
+        let config_lock = context
+            .build_script(&always_success_out_point, random_20bytes())
+            .expect("lock script");
+        let item_type = context
+            .build_script(
+                &cat_item_out_point,
+                Bytes::from(config_lock.calc_script_hash().as_slice().to_vec()),
+            )
+            .expect("script");
+        if items {
+            config.item_type_hash = Some(item_type.calc_script_hash().as_slice().to_vec());
+        }
+        let config_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(config_lock.clone())
+                .type_(Some(config_type.clone()).pack())
+                .build(),
+            Bytes::from(config.serialize()),
+        );
+
+        //args: owner lock hash + 改名费 + 配置cell的type hash
+        let mut type_args = Vec::from(config_lock.calc_script_hash().as_slice());
+        type_args.extend(DEFAULT_RENAME_FEE.to_be_bytes().iter());
+        type_args.extend(config_type.calc_script_hash().as_slice().iter());
+        let type_script = context
+            .build_script(&sourly_cat_out_point, Bytes::from(type_args))
+            .expect("script");
+
+        let cell_deps = vec![
+            CellDep::new_builder()
+                .out_point(sourly_cat_out_point)
+                .build(),
+            CellDep::new_builder()
+                .out_point(always_success_out_point.clone())
+                .build(),
+            CellDep::new_builder().out_point(config_out_point).build(),
+            CellDep::new_builder().out_point(cat_item_out_point).build(),
+        ];
+        CatEnv {
+            params: config.params(),
+            season: config.season(),
+            revive: config.revive(),
+            owner_lock: config_lock,
+            item_type,
+            always_success_out_point,
+            type_script,
+            cell_deps,
+        }
+    }
+
+    pub fn gen_lock(&self, context: &mut Context) -> Script {
+        context
+            .build_script(&self.always_success_out_point, random_20bytes())
+            .expect("lock script")
+    }
+
+    pub fn cat_output(&self, lock: &Script) -> CellOutput {
+        CellOutput::new_builder()
+            .capacity(500u64.pack())
+            .lock(lock.clone())
+            .type_(Some(self.type_script.clone()).pack())
+            .build()
+    }
+
+    pub fn create_cat(&self, context: &mut Context, lock: &Script, nft: &NFTData) -> CellInput {
+        let out_point =
+            context.create_cell(self.cat_output(lock), Bytes::from(nft.serialize().to_vec()));
+        CellInput::new_builder().previous_output(out_point).build()
+    }
+
+    pub fn build_tx(
+        &self,
+        context: &mut Context,
+        inputs: Vec<CellInput>,
+        outputs: Vec<CellOutput>,
+        outputs_data: Vec<Bytes>,
+        witnesses: Vec<Bytes>,
+    ) -> TransactionView {
+        let tx = TransactionBuilder::default()
+            .inputs(inputs)
+            .outputs(outputs)
+            .outputs_data(outputs_data.pack())
+            .cell_deps(self.cell_deps.clone())
+            .witnesses(witnesses.pack())
+            .build();
+        context.complete_tx(tx)
+    }
+
+    //两只随机的猫战斗，平局的话重新生成
+    pub fn gen_fight_tx(&self, context: &mut Context) -> TransactionView {
+        self.gen_fight_tx_with(context, |_| {}, |_| {})
+    }
+
+    //prepare修改战斗前的NFT，tamper修改按规则算出的战斗结果
+    pub fn gen_fight_tx_with<P: Fn(&mut NFTData), T: Fn(&mut NFTData)>(
+        &self,
+        context: &mut Context,
+        prepare: P,
+        tamper: T,
+    ) -> TransactionView {
+        let locks = [self.gen_lock(context), self.gen_lock(context)];
+        let lock_hash_0 = Vec::from(locks[0].calc_script_hash().as_slice());
+        let lock_hash_1 = Vec::from(locks[1].calc_script_hash().as_slice());
+        let (input_nft, (n, mut output_nft)) = loop {
+            let mut input_nft = vec![
+                NFTData::gen_random_nft(&lock_hash_0),
+                NFTData::gen_random_nft(&lock_hash_1),
+            ];
+            input_nft.iter_mut().for_each(|nft| prepare(nft));
+            //按迁移到当前赛季后的数据计算结果
+            let migrated: Vec<NFTData> = input_nft
+                .iter()
+                .map(|nft| nft.migrate(&self.season))
+                .collect();
+            if let Some(result) =
+                gen_fight_result(&migrated, &lock_hash_0, &lock_hash_1, 3000, &self.params)
+            {
+                break (input_nft, result);
+            }
+        };
+        output_nft.iter_mut().for_each(|nft| tamper(nft));
+        self.build_fight_tx(context, &locks, &input_nft, &output_nft, n)
+    }
+
+    pub fn build_fight_tx(
+        &self,
+        context: &mut Context,
+        locks: &[Script],
+        input_nft: &[NFTData],
+        output_nft: &[NFTData],
+        n: u16,
+    ) -> TransactionView {
+        let inputs = vec![
+            self.create_cat(context, &locks[0], &input_nft[0]),
+            self.create_cat(context, &locks[1], &input_nft[1]),
+        ];
+        let outputs = vec![self.cat_output(&locks[0]), self.cat_output(&locks[1])];
+        let outputs_data = output_nft
+            .iter()
+            .map(|nft| Bytes::from(nft.serialize().to_vec()))
+            .collect();
+        let witnesses = vec![
+            Bytes::new(),
+            WitnessArgsBuilder::default()
+                .input_type(Some(Bytes::from(n.to_be_bytes().to_vec())).pack())
+                .build()
+                .as_bytes(),
+        ];
+        self.build_tx(context, inputs, outputs, outputs_data, witnesses)
+    }
+
+    //把一只猫转给新的主人，modify可以修改输出的NFT
+    pub fn gen_transfer_tx<F: Fn(&mut NFTData)>(
+        &self,
+        context: &mut Context,
+        modify: F,
+    ) -> TransactionView {
+        self.gen_transfer_tx_with(context, |_| {}, modify)
+    }
+
+    //同上，prepare可以修改输入的NFT
+    pub fn gen_transfer_tx_with<P: Fn(&mut NFTData), F: Fn(&mut NFTData)>(
+        &self,
+        context: &mut Context,
+        prepare: P,
+        modify: F,
+    ) -> TransactionView {
+        let lock = self.gen_lock(context);
+        let lock_hash = Vec::from(lock.calc_script_hash().as_slice());
+        let mut input_nft = NFTData::gen_random_nft(&lock_hash);
+        prepare(&mut input_nft);
+        let mut output_nft = input_nft.clone();
+        modify(&mut output_nft);
+
+        let inputs = vec![self.create_cat(context, &lock, &input_nft)];
+        let outputs = vec![self.cat_output(&lock)];
+        let outputs_data = vec![Bytes::from(output_nft.serialize().to_vec())];
+        //always success不检查签名，lock里放任意数据表示已签名
+        let witnesses = vec![WitnessArgsBuilder::default()
+            .lock(Some(Bytes::from(vec![0u8; SIGNATURE_SIZE])).pack())
+            .build()
+            .as_bytes()];
+        self.build_tx(context, inputs, outputs, outputs_data, witnesses)
     }
 }
 
+//交易必须因为合约返回code而失败，sourly_cat的错误码见上面的ERR_*
+pub fn assert_script_error<T: std::fmt::Debug, E: std::fmt::Display>(
+    result: Result<T, E>,
+    code: i8,
+) {
+    let err = result.expect_err("verification should fail").to_string();
+    assert!(
+        err.contains(&format!("ValidationFailure({})", code))
+            || err.contains(&format!("error code {} ", code)),
+        "expected error code {}, got: {}",
+        code,
+        err
+    );
+}
+
 pub fn random_20bytes() -> Bytes {
     let mut rng = thread_rng();
     let mut buf = vec![0u8; 20];
@@ -190,25 +701,75 @@ pub fn random_20bytes() -> Bytes {
     Bytes::from(buf)
 }
 
-pub fn get_current_chain_id() -> u8 {
-    if let Ok(v) = env::var("CHAIN_ID") {
-        let chain_id = u8::from_str_radix(&v, 16).unwrap();
-        chain_id
-    } else {
-        1
-    }
+//pw-lock支持的链，BTC和DOGE还要区分公钥是否压缩
+#[derive(Clone, Copy, Debug)]
+pub struct Chain {
+    pub id: u8,
+    pub compressed: bool,
 }
 
-pub fn is_compressed() -> bool {
-    if let Ok(v) = env::var("COMPRESSED") {
-        let id = u8::from_str_radix(&v, 16).unwrap();
-        if id > 0 {
-            true
+impl Chain {
+    //所有链和公钥格式的组合，设置了CHAIN_ID或COMPRESSED环境变量时只取对应的组合
+    pub fn all() -> Vec<Chain> {
+        let chain_id = env::var("CHAIN_ID")
+            .ok()
+            .map(|v| u8::from_str_radix(&v, 16).unwrap());
+        let compressed = env::var("COMPRESSED")
+            .ok()
+            .map(|v| u8::from_str_radix(&v, 16).unwrap() > 0);
+        let mut chains = Vec::new();
+        for &id in [
+            CHAIN_ID_ETH,
+            CHAIN_ID_EOS,
+            CHAIN_ID_TRON,
+            CHAIN_ID_BTC,
+            CHAIN_ID_DOGE,
+        ]
+        .iter()
+        {
+            if chain_id.map_or(false, |chain_id| chain_id != id) {
+                continue;
+            }
+            for &mode in [true, false].iter() {
+                let chain = Chain {
+                    id,
+                    compressed: mode,
+                };
+                //其他链与公钥格式无关，只测一次
+                let wanted = if chain.is_bitcoin_like() {
+                    compressed.map_or(true, |compressed| compressed == mode)
+                } else {
+                    mode
+                };
+                if wanted {
+                    chains.push(chain);
+                }
+            }
+        }
+        assert!(
+            !chains.is_empty(),
+            "no chain matches CHAIN_ID and COMPRESSED"
+        );
+        chains
+    }
+
+    fn is_bitcoin_like(&self) -> bool {
+        self.id == CHAIN_ID_BTC || self.id == CHAIN_ID_DOGE
+    }
+
+    //pw-lock的args
+    pub fn pubkey_hash(&self, privkey: &Privkey) -> Bytes {
+        let pubkey = privkey.pubkey().expect("pubkey");
+        if self.is_bitcoin_like() {
+            let pubkey = if self.compressed {
+                pubkey_compressed(&pubkey)
+            } else {
+                pubkey_uncompressed(&pubkey)
+            };
+            ripemd_sha(&pubkey)
         } else {
-            false
+            eth160(pubkey)
         }
-    } else {
-        true
     }
 }
 
@@ -216,34 +777,38 @@ pub fn sign_tx_keccak256(
     dummy: &mut Context,
     tx: TransactionView,
     key: &Privkey,
+    chain: Chain,
 ) -> TransactionView {
-    sign_tx_keccak256_with_flag(dummy, tx, key, true)
+    sign_tx_keccak256_with_flag(dummy, tx, key, chain, true)
 }
 
 pub fn sign_tx_keccak256_with_flag(
     dummy: &mut Context,
     tx: TransactionView,
     key: &Privkey,
+    chain: Chain,
     set_chain_flag: bool,
 ) -> TransactionView {
     let witnesses_len = tx.witnesses().len();
-    sign_tx_by_input_group_keccak256_flag(dummy, tx, key, 0, witnesses_len, set_chain_flag)
+    sign_tx_by_input_group_keccak256_flag(dummy, tx, key, chain, 0, witnesses_len, set_chain_flag)
 }
 
 pub fn sign_tx_by_input_group_keccak256(
     dummy: &mut Context,
     tx: TransactionView,
     key: &Privkey,
+    chain: Chain,
     begin_index: usize,
     len: usize,
 ) -> TransactionView {
-    sign_tx_by_input_group_keccak256_flag(dummy, tx, key, begin_index, len, true)
+    sign_tx_by_input_group_keccak256_flag(dummy, tx, key, chain, begin_index, len, true)
 }
 
 pub fn sign_tx_by_input_group_keccak256_flag(
     _: &mut Context,
     tx: TransactionView,
     key: &Privkey,
+    chain: Chain,
     begin_index: usize,
     len: usize,
     set_chain_flag: bool,
@@ -280,7 +845,7 @@ pub fn sign_tx_by_input_group_keccak256_flag(
                     buf.into()
                 };
                 let mut lock = [0u8; SIGNATURE_SIZE + 1];
-                lock[0] = get_current_chain_id();
+                lock[0] = chain.id;
 
                 let witness_for_digest = witness
                     .clone()
@@ -301,8 +866,8 @@ pub fn sign_tx_by_input_group_keccak256_flag(
 
                 // blake2b.finalize(&mut message);
 
-                print!("chain-{}\n", get_current_chain_id());
-                if get_current_chain_id() == CHAIN_ID_ETH {
+                print!("chain-{}\n", chain.id);
+                if chain.id == CHAIN_ID_ETH {
                     // Ethereum personal sign prefix \x19Ethereum Signed Message:\n32
                     let prefix: [u8; 28] = [
                         0x19, 0x45, 0x74, 0x68, 0x65, 0x72, 0x65, 0x75, 0x6d, 0x20, 0x53, 0x69,
@@ -318,7 +883,7 @@ pub fn sign_tx_by_input_group_keccak256_flag(
 
                     let sig = key.sign_recoverable(&message1).expect("sign");
                     lock[start_index..end_index].copy_from_slice(&sig.serialize().to_vec());
-                } else if get_current_chain_id() == CHAIN_ID_TRON {
+                } else if chain.id == CHAIN_ID_TRON {
                     // Tron sign prefix \x19TRON Signed Message:\n32
                     let prefix: [u8; 24] = [
                         0x19, 0x54, 0x52, 0x4f, 0x4e, 0x20, 0x53, 0x69, 0x67, 0x6e, 0x65, 0x64,
@@ -333,7 +898,7 @@ pub fn sign_tx_by_input_group_keccak256_flag(
 
                     let sig = key.sign_recoverable(&message1).expect("sign");
                     lock[start_index..end_index].copy_from_slice(&sig.serialize().to_vec());
-                } else if get_current_chain_id() == CHAIN_ID_EOS {
+                } else if chain.id == CHAIN_ID_EOS {
                     // EOS scatter.getArbitrarySignature() requires each word of message
                     // to be less than 12 characters. so insert blank char every 12 char for
                     // transaction message digest.
@@ -353,7 +918,7 @@ pub fn sign_tx_by_input_group_keccak256_flag(
                     let message1 = H256::from(message);
                     let sig = key.sign_recoverable(&message1).expect("sign");
                     lock[start_index..end_index].copy_from_slice(&sig.serialize().to_vec());
-                } else if get_current_chain_id() == CHAIN_ID_BTC {
+                } else if chain.id == CHAIN_ID_BTC {
                     let message_hex = faster_hex::hex_string(&message).unwrap();
                     println!("message_hex {}, len {}", message_hex, message_hex.len());
 
@@ -370,7 +935,7 @@ pub fn sign_tx_by_input_group_keccak256_flag(
                     let sig_vec = sig.serialize().to_vec();
 
                     let mut data = [0u8; SIGNATURE_SIZE];
-                    if is_compressed() {
+                    if chain.compressed {
                         data[0] = sig_vec[64] + 27 + 4;
                     } else {
                         data[0] = sig_vec[64] + 27;
@@ -378,7 +943,7 @@ pub fn sign_tx_by_input_group_keccak256_flag(
                     data[1..].copy_from_slice(&sig_vec[..64]);
 
                     lock[start_index..end_index].copy_from_slice(&data);
-                } else if get_current_chain_id() == CHAIN_ID_DOGE {
+                } else if chain.id == CHAIN_ID_DOGE {
                     let message_hex = faster_hex::hex_string(&message).unwrap();
                     println!("message_hex {}, len {}", message_hex, message_hex.len());
                     let mut sha256hasher = Sha256::default();
@@ -394,7 +959,7 @@ pub fn sign_tx_by_input_group_keccak256_flag(
                     let sig_vec = sig.serialize().to_vec();
 
                     let mut data = [0u8; SIGNATURE_SIZE];
-                    if is_compressed() {
+                    if chain.compressed {
                         data[0] = sig_vec[64] + 27 + 4;
                     } else {
                         data[0] = sig_vec[64] + 27;
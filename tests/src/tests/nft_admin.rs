@@ -0,0 +1,225 @@
+use super::*;
+use ckb_testtool::{builtin::ALWAYS_SUCCESS, context::Context};
+use ckb_tool::ckb_types::{
+    bytes::Bytes,
+    core::{TransactionBuilder, TransactionView},
+    packed::*,
+    prelude::*,
+};
+
+fn gen_admin_config(threshold: u8, admins: &[Script]) -> Bytes {
+    let config = Config {
+        threshold,
+        admin_lock_hashes: admins
+            .iter()
+            .map(|admin| Vec::from(admin.calc_script_hash().as_slice()))
+            .collect(),
+        ..Default::default()
+    };
+    Bytes::from(config.serialize())
+}
+
+//由signers签名铸造NFT，合约args引用配置cell
+fn gen_tx_for_admin_mint(
+    context: &mut Context,
+    config_data: Option<Bytes>,
+    owner_lock: &Script,
+    signers: &[Script],
+    always_success_out_point: &OutPoint,
+) -> TransactionView {
+    //load_script_bin
+    let sourly_cat_out_point = context.deploy_cell(SOURLY_CAT_BIN.clone());
+    let sourly_cat_dep = CellDep::new_builder()
+        .out_point(sourly_cat_out_point.clone())
+        .build();
+    let always_success_dep = CellDep::new_builder()
+        .out_point(always_success_out_point.clone())
+        .build();
+
+    //用always success代替type id，只要type hash唯一即可
+    let config_type = context
+        .build_script(always_success_out_point, random_20bytes())
+        .expect("script");
+    let config_type_hash = config_type.calc_script_hash();
+
+    //args: owner lock hash + 改名费 + 配置cell的type hash
+    let mut type_args = Vec::from(owner_lock.calc_script_hash().as_slice());
+    type_args.extend(10i32.to_be_bytes().iter());
+    type_args.extend(config_type_hash.as_slice().iter());
+    let type_script = context
+        .build_script(&sourly_cat_out_point, Bytes::from(type_args))
+        .expect("script");
+
+    let mut cell_deps = vec![sourly_cat_dep, always_success_dep];
+    if let Some(config_data) = config_data {
+        let config_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(owner_lock.clone())
+                .type_(Some(config_type).pack())
+                .build(),
+            config_data,
+        );
+        cell_deps.push(CellDep::new_builder().out_point(config_out_point).build());
+    }
+
+    // prepare cells
+    let inputs: Vec<CellInput> = signers
+        .iter()
+        .map(|signer| {
+            let out_point = context.create_cell(
+                CellOutput::new_builder()
+                    .capacity(1000u64.pack())
+                    .lock(signer.clone())
+                    .build(),
+                Bytes::new(),
+            );
+            CellInput::new_builder().previous_output(out_point).build()
+        })
+        .collect();
+
+    let lock_script_user = context
+        .build_script(always_success_out_point, random_20bytes())
+        .expect("lock script");
+    let user_lock_hash = Vec::from(lock_script_user.calc_script_hash().as_slice());
+    let outputs = vec![
+        CellOutput::new_builder()
+            .capacity(500u64.pack())
+            .lock(lock_script_user.clone())
+            .type_(Some(type_script.clone()).pack())
+            .build(),
+        CellOutput::new_builder()
+            .capacity(500u64.pack())
+            .lock(lock_script_user.clone())
+            .type_(Some(type_script.clone()).pack())
+            .build(),
+    ];
+    let outputs_data = vec![
+        Bytes::from(
+            NFTData::gen_random_nft(&user_lock_hash)
+                .serialize()
+                .to_vec(),
+        ),
+        Bytes::from(
+            NFTData::gen_random_nft(&user_lock_hash)
+                .serialize()
+                .to_vec(),
+        ),
+    ];
+
+    let witnesses: Vec<Bytes> = inputs.iter().map(|_| Bytes::new()).collect();
+
+    // build transaction
+    let tx = TransactionBuilder::default()
+        .inputs(inputs)
+        .outputs(outputs)
+        .outputs_data(outputs_data.pack())
+        .cell_deps(cell_deps)
+        .witnesses(witnesses.pack())
+        .build();
+    let tx = context.complete_tx(tx);
+    tx
+}
+
+fn gen_locks(context: &mut Context, out_point: &OutPoint, count: usize) -> Vec<Script> {
+    (0..count)
+        .map(|_| {
+            context
+                .build_script(out_point, random_20bytes())
+                .expect("lock script")
+        })
+        .collect()
+}
+
+#[test]
+fn test_admin_mint() {
+    let mut context = Context::default();
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let locks = gen_locks(&mut context, &always_success_out_point, 3);
+    let (owner, admins) = (&locks[0], &locks[1..]);
+
+    //任意一个管理员都可以铸造
+    for admin in admins {
+        let config = gen_admin_config(1, admins);
+        let tx = gen_tx_for_admin_mint(
+            &mut context,
+            Some(config),
+            owner,
+            &[admin.clone()],
+            &always_success_out_point,
+        );
+        let cycles = context
+            .verify_tx(&tx, MAX_CYCLES)
+            .expect("pass verification");
+        println!("consume cycles: {}", cycles);
+    }
+}
+
+#[test]
+fn test_admin_mint_threshold() {
+    let mut context = Context::default();
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let locks = gen_locks(&mut context, &always_success_out_point, 3);
+    let (owner, admins) = (&locks[0], &locks[1..]);
+
+    //2-of-2，只有一个管理员签名
+    let config = gen_admin_config(2, admins);
+    let tx = gen_tx_for_admin_mint(
+        &mut context,
+        Some(config),
+        owner,
+        &admins[..1],
+        &always_success_out_point,
+    );
+    //不是管理员模式时按转账检查，输出的猫没有对应的输入
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_INPUT_OUTPUT);
+
+    //两个管理员都签名
+    let config = gen_admin_config(2, admins);
+    let tx = gen_tx_for_admin_mint(
+        &mut context,
+        Some(config),
+        owner,
+        admins,
+        &always_success_out_point,
+    );
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_admin_rotated_owner() {
+    let mut context = Context::default();
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let locks = gen_locks(&mut context, &always_success_out_point, 2);
+    let (owner, admin) = (&locks[0], &locks[1]);
+
+    //args中的Owner已经被替换，不能再铸造
+    let config = gen_admin_config(1, &[admin.clone()]);
+    let tx = gen_tx_for_admin_mint(
+        &mut context,
+        Some(config),
+        owner,
+        &[owner.clone()],
+        &always_success_out_point,
+    );
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_INPUT_OUTPUT);
+}
+
+#[test]
+fn test_admin_config_missing() {
+    let mut context = Context::default();
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let locks = gen_locks(&mut context, &always_success_out_point, 1);
+
+    let tx = gen_tx_for_admin_mint(
+        &mut context,
+        None,
+        &locks[0],
+        &[locks[0].clone()],
+        &always_success_out_point,
+    );
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_CONFIG_MISSING);
+}
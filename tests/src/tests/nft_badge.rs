@@ -0,0 +1,119 @@
+use super::*;
+use ckb_testtool::context::Context;
+
+fn gen_config(params: Params) -> Config {
+    Config {
+        threshold: 1,
+        //测试中不需要管理员签名
+        admin_lock_hashes: vec![vec![0u8; 32]],
+        params: Some(params),
+        ..Default::default()
+    }
+}
+
+fn is_winner(nft: &NFTData) -> bool {
+    nft.win_streak > 0
+}
+
+//战斗后用check检查双方得到的徽章，再验证交易
+fn verify_fight<P: Fn(&mut NFTData), C: Fn(&NFTData)>(params: Params, prepare: P, check: C) {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(params));
+    let tx = env.gen_fight_tx_with(&mut context, prepare, |nft| check(nft));
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_badge_first_win() {
+    verify_fight(
+        Params::default(),
+        |_| {},
+        |nft| {
+            if is_winner(nft) {
+                assert_eq!(nft.badges, BADGE_FIRST_WIN);
+            } else {
+                assert_eq!(nft.badges, 0);
+            }
+        },
+    );
+}
+
+#[test]
+fn test_badge_win_streak() {
+    verify_fight(
+        Params::default(),
+        |nft| {
+            nft.badges = BADGE_FIRST_WIN;
+            nft.win_streak = 2;
+        },
+        |nft| {
+            if is_winner(nft) {
+                assert_eq!(nft.win_streak, 3);
+                assert_eq!(nft.badges, BADGE_FIRST_WIN | BADGE_WIN_STREAK);
+            } else {
+                assert_eq!(nft.badges, BADGE_FIRST_WIN);
+            }
+        },
+    );
+}
+
+#[test]
+fn test_badge_hidden_reward() {
+    //攻击力不超过100，输的一方不扣fish，从0开始必然触发隐藏奖励
+    let params = Params {
+        loser_divisor: 255,
+        ..Default::default()
+    };
+    verify_fight(
+        params,
+        |nft| nft.fishes = 0,
+        |nft| {
+            if !is_winner(nft) {
+                assert_eq!(nft.badges, BADGE_HIDDEN_REWARD);
+            }
+        },
+    );
+}
+
+#[test]
+fn test_badge_negative_survivor() {
+    //输的一方扣掉全部攻击力，从0开始必然变成负数
+    let params = Params {
+        loser_divisor: 1,
+        ..Default::default()
+    };
+    verify_fight(
+        params,
+        |nft| nft.fishes = 0,
+        |nft| {
+            if !is_winner(nft) {
+                assert!(nft.fishes < 0);
+                assert_eq!(nft.badges, BADGE_NEGATIVE_SURVIVOR);
+            }
+        },
+    );
+}
+
+#[test]
+fn test_badge_not_earned() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(Params::default()));
+    //赢了却不记录徽章
+    let tx = env.gen_fight_tx_with(&mut context, |_| {}, |nft| nft.badges = 0);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_RESULT);
+}
+
+#[test]
+fn test_badge_forged_by_transfer() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(Params::default()));
+    let tx = env.gen_transfer_tx(&mut context, |nft| nft.badges |= BADGE_HIDDEN_REWARD);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_TRANSFER);
+}
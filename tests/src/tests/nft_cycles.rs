@@ -0,0 +1,237 @@
+use super::*;
+use ckb_testtool::{builtin::ALWAYS_SUCCESS, context::Context};
+use ckb_tool::ckb_types::{bytes::Bytes, core::TransactionView, packed::*, prelude::*};
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, env, fs, path::Path};
+
+//各种交易消耗的cycles的基准，格式见README
+const BASELINE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/benchmarks/cycles.json");
+//设置这个环境变量时用本次的结果更新基准，而不是比较
+const UPDATE_ENV_VAR: &str = "UPDATE_CYCLES_BASELINE";
+//基准文件中没有阈值时，允许超出基准的百分比
+const DEFAULT_THRESHOLD: u64 = 5;
+//一个交易中铸造或转移的猫的数量
+const CAT_COUNTS: [usize; 5] = [1, 2, 4, 8, 16];
+
+//启用装备，管理员用来铸造
+fn gen_env(context: &mut Context) -> (CatEnv, Script) {
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let admin = context
+        .build_script(&always_success_out_point, random_20bytes())
+        .expect("lock script");
+    let config = Config {
+        threshold: 1,
+        admin_lock_hashes: vec![Vec::from(admin.calc_script_hash().as_slice())],
+        ..Default::default()
+    };
+    (CatEnv::with_items(context, &config), admin)
+}
+
+//管理员铸造count只猫
+fn gen_mint_tx(
+    context: &mut Context,
+    env: &CatEnv,
+    admin: &Script,
+    count: usize,
+) -> TransactionView {
+    let admin_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(admin.clone())
+            .build(),
+        Bytes::new(),
+    );
+    let inputs = vec![CellInput::new_builder()
+        .previous_output(admin_out_point)
+        .build()];
+    let lock = env.gen_lock(context);
+    let lock_hash = Vec::from(lock.calc_script_hash().as_slice());
+    let outputs = (0..count).map(|_| env.cat_output(&lock)).collect();
+    let outputs_data = (0..count)
+        .map(|_| Bytes::from(NFTData::gen_random_nft(&lock_hash).serialize()))
+        .collect();
+    env.build_tx(context, inputs, outputs, outputs_data, vec![Bytes::new()])
+}
+
+//count只猫各自转给新的主人
+fn gen_transfer_tx(context: &mut Context, env: &CatEnv, count: usize) -> TransactionView {
+    let mut inputs = Vec::with_capacity(count);
+    let mut outputs = Vec::with_capacity(count);
+    let mut outputs_data = Vec::with_capacity(count);
+    for _ in 0..count {
+        let lock = env.gen_lock(context);
+        let nft = NFTData::gen_random_nft(&Vec::from(lock.calc_script_hash().as_slice()));
+        inputs.push(env.create_cat(context, &lock, &nft));
+        outputs.push(env.cat_output(&env.gen_lock(context)));
+        outputs_data.push(Bytes::from(nft.serialize()));
+    }
+    let witnesses = vec![Bytes::new(); count];
+    env.build_tx(context, inputs, outputs, outputs_data, witnesses)
+}
+
+//猫0装备着items和猫1战斗，装备作为cell dep
+fn gen_equipped_fight_tx(context: &mut Context, env: &CatEnv, items: &[Item]) -> TransactionView {
+    let locks = [env.gen_lock(context), env.gen_lock(context)];
+    let lock_hash_0 = Vec::from(locks[0].calc_script_hash().as_slice());
+    let lock_hash_1 = Vec::from(locks[1].calc_script_hash().as_slice());
+    let (input_nft, (n, output_nft)) = loop {
+        let input_nft = vec![
+            NFTData::gen_random_nft(&lock_hash_0),
+            NFTData::gen_random_nft(&lock_hash_1),
+        ];
+        let mut equipped: Statistics = input_nft[0].hash.into();
+        for item in items {
+            equipped.equip(&item.bonus);
+        }
+        if let Some(result) = gen_fight_result_with_stats(
+            &input_nft,
+            &lock_hash_0,
+            &lock_hash_1,
+            3000,
+            &env.params,
+            [equipped, input_nft[1].hash.into()],
+        ) {
+            break (input_nft, result);
+        }
+    };
+    let item_deps: Vec<CellDep> = items
+        .iter()
+        .map(|item| {
+            let out_point = context.create_cell(
+                CellOutput::new_builder()
+                    .capacity(500u64.pack())
+                    .lock(locks[0].clone())
+                    .type_(Some(env.item_type.clone()).pack())
+                    .build(),
+                Bytes::from(item.serialize()),
+            );
+            CellDep::new_builder().out_point(out_point).build()
+        })
+        .collect();
+    env.build_fight_tx(context, &locks, &input_nft, &output_nft, n)
+        .as_advanced_builder()
+        .cell_deps(item_deps)
+        .build()
+}
+
+fn gen_items() -> Vec<Item> {
+    [ITEM_KIND_COLLAR, ITEM_KIND_CLAWS, ITEM_KIND_ARMOUR]
+        .iter()
+        .map(|&kind| Item {
+            kind,
+            bonus: Statistics {
+                hp: 10,
+                atk: 10,
+                def: 10,
+                lck: 10,
+            },
+        })
+        .collect()
+}
+
+//每种交易消耗的cycles，包括always success lock
+fn measure() -> BTreeMap<String, u64> {
+    let mut context = Context::default();
+    let (env, admin) = gen_env(&mut context);
+    let mut txs = Vec::new();
+    for &count in CAT_COUNTS.iter() {
+        txs.push((
+            format!("mint_{}", count),
+            gen_mint_tx(&mut context, &env, &admin, count),
+        ));
+    }
+    for &count in CAT_COUNTS.iter() {
+        txs.push((
+            format!("transfer_{}", count),
+            gen_transfer_tx(&mut context, &env, count),
+        ));
+    }
+    txs.push(("fight".to_string(), env.gen_fight_tx(&mut context)));
+    let items = gen_items();
+    txs.push((
+        format!("fight_items_{}", items.len()),
+        gen_equipped_fight_tx(&mut context, &env, &items),
+    ));
+
+    txs.into_iter()
+        .map(|(name, tx)| {
+            let cycles = context
+                .verify_tx(&tx, MAX_CYCLES)
+                .unwrap_or_else(|err| panic!("{} failed: {}", name, err));
+            (name, cycles)
+        })
+        .collect()
+}
+
+fn write_baseline(threshold: u64, cycles: &BTreeMap<String, u64>) {
+    let doc = json!({ "threshold": threshold, "cycles": cycles });
+    fs::create_dir_all(Path::new(BASELINE_PATH).parent().unwrap()).expect("create benchmarks dir");
+    fs::write(BASELINE_PATH, serde_json::to_string_pretty(&doc).unwrap()).expect("write baseline");
+}
+
+//与基准比较，超出阈值的交易视为回归；更新基准：
+//UPDATE_CYCLES_BASELINE=1 cargo test test_cycles_regression
+#[test]
+fn test_cycles_regression() {
+    let cycles = measure();
+    for (name, cycles) in cycles.iter() {
+        println!("{}: {} cycles", name, cycles);
+    }
+
+    let baseline: Option<Value> = fs::read_to_string(BASELINE_PATH)
+        .ok()
+        .map(|content| serde_json::from_str(&content).expect("parse baseline"));
+    if env::var(UPDATE_ENV_VAR).is_ok() {
+        let threshold = baseline
+            .as_ref()
+            .and_then(|baseline| baseline["threshold"].as_u64())
+            .unwrap_or(DEFAULT_THRESHOLD);
+        write_baseline(threshold, &cycles);
+        println!("cycles baseline written to {}", BASELINE_PATH);
+        return;
+    }
+    //基准文件必须提交，缺少时不能当作通过
+    let baseline = baseline.unwrap_or_else(|| {
+        panic!(
+            "no cycles baseline at {}, run with {}=1 to write it",
+            BASELINE_PATH, UPDATE_ENV_VAR
+        )
+    });
+    let threshold = baseline["threshold"].as_u64().unwrap_or(DEFAULT_THRESHOLD);
+
+    let mut regressions = Vec::new();
+    for (name, &cycles) in cycles.iter() {
+        let expected = match baseline["cycles"][name].as_u64() {
+            Some(expected) => expected,
+            None => {
+                regressions.push(format!("{}: no baseline", name));
+                continue;
+            }
+        };
+        //0是没有测量过的占位值，与它比较任何交易都会被当作回归
+        assert!(
+            expected > 0,
+            "cycles baseline of {} is 0, rerun with {}=1 to record the real cycles",
+            name,
+            UPDATE_ENV_VAR
+        );
+        if cycles * 100 > expected * (100 + threshold) {
+            regressions.push(format!(
+                "{}: {} cycles, baseline {}",
+                name, cycles, expected
+            ));
+        } else if cycles * 100 < expected * 100u64.saturating_sub(threshold) {
+            println!(
+                "{}: {} cycles, baseline {}, consider updating the baseline",
+                name, cycles, expected
+            );
+        }
+    }
+    assert!(
+        regressions.is_empty(),
+        "cycles regressed beyond {}%, rerun with {}=1 if expected:\n{}",
+        threshold,
+        UPDATE_ENV_VAR,
+        regressions.join("\n")
+    );
+}
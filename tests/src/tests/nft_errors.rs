@@ -0,0 +1,452 @@
+use super::*;
+use ckb_testtool::context::Context;
+use ckb_tool::ckb_types::{bytes::Bytes, core::TransactionView, packed::*, prelude::*};
+
+fn gen_config(flags: u8) -> Config {
+    Config {
+        threshold: 1,
+        //测试中不需要管理员签名
+        admin_lock_hashes: vec![vec![0u8; 32]],
+        flags,
+        ..Default::default()
+    }
+}
+
+struct Fight {
+    locks: [Script; 2],
+    input_nft: Vec<NFTData>,
+    output_nft: Vec<NFTData>,
+    n: u16,
+}
+
+//按规则算出一场战斗，要求回合数大于1，这样n-1一定分不出胜负
+fn gen_fight(context: &mut Context, env: &CatEnv) -> Fight {
+    let locks = [env.gen_lock(context), env.gen_lock(context)];
+    let lock_hash_0 = Vec::from(locks[0].calc_script_hash().as_slice());
+    let lock_hash_1 = Vec::from(locks[1].calc_script_hash().as_slice());
+    loop {
+        let input_nft = vec![
+            NFTData::gen_random_nft(&lock_hash_0),
+            NFTData::gen_random_nft(&lock_hash_1),
+        ];
+        match gen_fight_result(&input_nft, &lock_hash_0, &lock_hash_1, 3000, &env.params) {
+            Some((n, output_nft)) if n > 1 => {
+                return Fight {
+                    locks,
+                    input_nft,
+                    output_nft,
+                    n,
+                }
+            }
+            _ => continue,
+        }
+    }
+}
+
+fn fight_witnesses(n: u16) -> Vec<Bytes> {
+    vec![
+        Bytes::new(),
+        WitnessArgsBuilder::default()
+            .input_type(Some(Bytes::from(n.to_be_bytes().to_vec())).pack())
+            .build()
+            .as_bytes(),
+    ]
+}
+
+fn cat_data(nft: &[NFTData]) -> Vec<Bytes> {
+    nft.iter()
+        .map(|nft| Bytes::from(nft.serialize().to_vec()))
+        .collect()
+}
+
+//属于lock的装备cell，data可以是不合法的
+fn create_item(context: &mut Context, env: &CatEnv, lock: &Script, data: Vec<u8>) -> OutPoint {
+    context.create_cell(item_output(env, lock), Bytes::from(data))
+}
+
+fn item_output(env: &CatEnv, lock: &Script) -> CellOutput {
+    CellOutput::new_builder()
+        .capacity(500u64.pack())
+        .lock(lock.clone())
+        .type_(Some(env.item_type.clone()).pack())
+        .build()
+}
+
+//猫0带着作为cell dep的装备战斗，装备在验证战斗结果之前就会报错
+fn gen_equipped_fight_tx(
+    context: &mut Context,
+    env: &CatEnv,
+    items: Vec<Vec<u8>>,
+) -> TransactionView {
+    let fight = gen_fight(context, env);
+    let item_deps: Vec<CellDep> = items
+        .into_iter()
+        .map(|data| {
+            let out_point = create_item(context, env, &fight.locks[0], data);
+            CellDep::new_builder().out_point(out_point).build()
+        })
+        .collect();
+    env.build_fight_tx(
+        context,
+        &fight.locks,
+        &fight.input_nft,
+        &fight.output_nft,
+        fight.n,
+    )
+    .as_advanced_builder()
+    .cell_deps(item_deps)
+    .build()
+}
+
+fn claws() -> Item {
+    Item {
+        kind: ITEM_KIND_CLAWS,
+        bonus: Statistics {
+            hp: 0,
+            atk: 80,
+            def: 0,
+            lck: 10,
+        },
+    }
+}
+
+#[test]
+fn test_fight_wrong_round_count() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    let fight = gen_fight(&mut context, &env);
+    let tx = env.build_fight_tx(
+        &mut context,
+        &fight.locks,
+        &fight.input_nft,
+        &fight.output_nft,
+        fight.n - 1,
+    );
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_RESULT);
+}
+
+#[test]
+fn test_fight_changed_name() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    let tx = env.gen_fight_tx_with(&mut context, |_| {}, |nft| nft.name[0] = b'X');
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_RESULT);
+}
+
+#[test]
+fn test_fight_tampered_fishes() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    let tx = env.gen_fight_tx_with(&mut context, |_| {}, |nft| nft.fishes += 1);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_RESULT);
+}
+
+#[test]
+fn test_fight_swapped_locks() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    let fight = gen_fight(&mut context, &env);
+    let inputs = vec![
+        env.create_cat(&mut context, &fight.locks[0], &fight.input_nft[0]),
+        env.create_cat(&mut context, &fight.locks[1], &fight.input_nft[1]),
+    ];
+    //双方的lock互换
+    let outputs = vec![
+        env.cat_output(&fight.locks[1]),
+        env.cat_output(&fight.locks[0]),
+    ];
+    let tx = env.build_tx(
+        &mut context,
+        inputs,
+        outputs,
+        cat_data(&fight.output_nft),
+        fight_witnesses(fight.n),
+    );
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_UNMATCH_PLAYER);
+}
+
+#[test]
+fn test_fight_third_input() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    let fight = gen_fight(&mut context, &env);
+    let third_lock = env.gen_lock(&mut context);
+    let third_hash = Vec::from(third_lock.calc_script_hash().as_slice());
+    //第三只猫没有对应的输出
+    let inputs = vec![
+        env.create_cat(&mut context, &fight.locks[0], &fight.input_nft[0]),
+        env.create_cat(&mut context, &fight.locks[1], &fight.input_nft[1]),
+        env.create_cat(
+            &mut context,
+            &third_lock,
+            &NFTData::gen_random_nft(&third_hash),
+        ),
+    ];
+    let outputs = vec![
+        env.cat_output(&fight.locks[0]),
+        env.cat_output(&fight.locks[1]),
+    ];
+    let tx = env.build_tx(
+        &mut context,
+        inputs,
+        outputs,
+        cat_data(&fight.output_nft),
+        fight_witnesses(fight.n),
+    );
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_INPUT_OUTPUT);
+}
+
+#[test]
+fn test_fight_cats_not_first_inputs() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    let fight = gen_fight(&mut context, &env);
+    let other_lock = env.gen_lock(&mut context);
+    let other_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(other_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+    //第一个输入是普通cell，猫在输入1，2
+    let inputs = vec![
+        CellInput::new_builder()
+            .previous_output(other_out_point)
+            .build(),
+        env.create_cat(&mut context, &fight.locks[0], &fight.input_nft[0]),
+        env.create_cat(&mut context, &fight.locks[1], &fight.input_nft[1]),
+    ];
+    let outputs = vec![
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(other_lock)
+            .build(),
+        env.cat_output(&fight.locks[0]),
+        env.cat_output(&fight.locks[1]),
+    ];
+    let mut outputs_data = vec![Bytes::new()];
+    outputs_data.extend(cat_data(&fight.output_nft));
+    //战斗回合数在第二只猫的见证中
+    let mut witnesses = vec![Bytes::new()];
+    witnesses.extend(fight_witnesses(fight.n));
+    let tx = env.build_tx(&mut context, inputs, outputs, outputs_data, witnesses);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_INPUT_OUTPUT);
+}
+
+#[test]
+fn test_fight_paused() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(CONFIG_FLAG_PAUSED));
+    let tx = env.gen_fight_tx(&mut context);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_PAUSED);
+}
+
+#[test]
+fn test_fight_future_season() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    //配置中是第0赛季
+    let tx = env.gen_fight_tx_with(&mut context, |nft| nft.season = 1, |_| {});
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_SEASON);
+}
+
+#[test]
+fn test_fight_knocked_out() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    let tx = env.gen_fight_tx_with(&mut context, |nft| nft.fishes = -1, |_| {});
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_KNOCKED_OUT);
+}
+
+#[test]
+fn test_fight_invalid_item() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config(0));
+    //种类9不存在
+    let tx = gen_equipped_fight_tx(&mut context, &env, vec![vec![9, 10, 10, 10, 10]]);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_INVALID_ITEM);
+}
+
+#[test]
+fn test_fight_duplicate_item() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config(0));
+    let tx = gen_equipped_fight_tx(
+        &mut context,
+        &env,
+        vec![claws().serialize(), claws().serialize()],
+    );
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_DUPLICATE_ITEM);
+}
+
+#[test]
+fn test_fight_potion_not_burned() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config(0));
+    let fight = gen_fight(&mut context, &env);
+    let potion = Item {
+        kind: ITEM_KIND_POTION,
+        bonus: Statistics {
+            hp: 0,
+            atk: 50,
+            def: 50,
+            lck: 0,
+        },
+    }
+    .serialize();
+    let potion_out_point = create_item(&mut context, &env, &fight.locks[0], potion.clone());
+    //药水作为输入，又原样出现在输出中
+    let inputs = vec![
+        env.create_cat(&mut context, &fight.locks[0], &fight.input_nft[0]),
+        env.create_cat(&mut context, &fight.locks[1], &fight.input_nft[1]),
+        CellInput::new_builder()
+            .previous_output(potion_out_point)
+            .build(),
+    ];
+    let outputs = vec![
+        env.cat_output(&fight.locks[0]),
+        env.cat_output(&fight.locks[1]),
+        item_output(&env, &fight.locks[0]),
+    ];
+    let mut outputs_data = cat_data(&fight.output_nft);
+    outputs_data.push(Bytes::from(potion));
+    let mut witnesses = fight_witnesses(fight.n);
+    witnesses.push(Bytes::new());
+    let tx = env.build_tx(&mut context, inputs, outputs, outputs_data, witnesses);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_POTION_NOT_BURNED);
+}
+
+#[test]
+fn test_transfer_short_data() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    let tx = env.gen_transfer_tx(&mut context, |_| {});
+    //输出的数据比最短的格式还少一个字节
+    let tx = tx
+        .as_advanced_builder()
+        .set_outputs_data(vec![Bytes::from(vec![0u8; 39]).pack()])
+        .build();
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_ENCODING);
+}
+
+#[test]
+fn test_transfer_tampered_fishes() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    let tx = env.gen_transfer_tx(&mut context, |nft| nft.fishes += 100);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_TRANSFER);
+}
+
+#[test]
+fn test_transfer_tampered_hash() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    let tx = env.gen_transfer_tx(&mut context, |nft| nft.hash[0] ^= 1);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_TRANSFER);
+}
+
+#[test]
+fn test_rename_without_fee() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    //改名但没有扣除改名费
+    let tx = env.gen_transfer_tx(&mut context, |nft| nft.name[0] = b'X');
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_RENAME);
+}
+
+#[test]
+fn test_rename_invalid_name() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    let tx = env.gen_transfer_tx(&mut context, |nft| {
+        nft.name[0] = 0x07;
+        nft.fishes -= DEFAULT_RENAME_FEE;
+    });
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_INVALID_NAME);
+}
+
+#[test]
+fn test_revive_wrong_fishes() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    let revived = env.revive.fishes + 1;
+    let tx = env.gen_transfer_tx_with(
+        &mut context,
+        |nft| nft.fishes = -1,
+        |nft| nft.fishes = revived,
+    );
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_REVIVE);
+}
+
+#[test]
+fn test_invalid_extension() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    //类型0保留
+    let tx = env.gen_transfer_tx(&mut context, |nft| nft.extensions = vec![(0, vec![1])]);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_INVALID_EXTENSION);
+}
+
+#[test]
+fn test_changed_immutable_extension() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    let tx = env.gen_transfer_tx(&mut context, |nft| {
+        nft.extensions = vec![(EXTENSION_ATTRIBUTES, b"rare".to_vec())]
+    });
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_EXTENSION);
+}
+
+#[test]
+fn test_config_missing() {
+    let mut context = Context::default();
+    let mut env = CatEnv::new(&mut context, &gen_config(0));
+    //args中的配置cell type hash找不到
+    let mut args: Vec<u8> = env.type_script.args().unpack();
+    let len = args.len();
+    args[len - 1] ^= 1;
+    env.type_script = env
+        .type_script
+        .clone()
+        .as_builder()
+        .args(Bytes::from(args).pack())
+        .build();
+    let tx = env.gen_transfer_tx(&mut context, |_| {});
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_CONFIG_MISSING);
+}
+
+#[test]
+fn test_invalid_config() {
+    let mut context = Context::default();
+    //阈值为0
+    let config = Config {
+        threshold: 0,
+        ..gen_config(0)
+    };
+    let env = CatEnv::new(&mut context, &config);
+    let tx = env.gen_transfer_tx(&mut context, |_| {});
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_INVALID_CONFIG);
+}
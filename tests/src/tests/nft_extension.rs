@@ -0,0 +1,136 @@
+use super::*;
+use ckb_testtool::context::Context;
+
+fn gen_config() -> Config {
+    Config {
+        threshold: 1,
+        //测试中不需要管理员签名
+        admin_lock_hashes: vec![vec![0u8; 32]],
+        ..Default::default()
+    }
+}
+
+fn with_extensions(nft: &mut NFTData) {
+    nft.extensions = vec![
+        (EXTENSION_IMAGE, b"ipfs://cat.png".to_vec()),
+        (EXTENSION_DESCRIPTION, "一只橘猫".as_bytes().to_vec()),
+        (EXTENSION_ATTRIBUTES, b"{\"color\":\"orange\"}".to_vec()),
+    ];
+}
+
+fn set_extension(nft: &mut NFTData, kind: u8, value: &[u8]) {
+    for extension in nft.extensions.iter_mut() {
+        if extension.0 == kind {
+            extension.1 = value.to_vec();
+        }
+    }
+}
+
+fn rename(nft: &mut NFTData) {
+    nft.name = *b"Tom\0\0\0\0\0\0\0\0\0\0\0\0\0";
+    nft.fishes -= DEFAULT_RENAME_FEE;
+}
+
+#[test]
+fn test_extension_transfer() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let tx = env.gen_transfer_tx_with(&mut context, with_extensions, |_| {});
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_extension_fight() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let tx = env.gen_fight_tx_with(&mut context, with_extensions, |_| {});
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+
+    //战斗不能修改扩展记录
+    let tx = env.gen_fight_tx_with(&mut context, with_extensions, |nft| {
+        set_extension(nft, EXTENSION_DESCRIPTION, b"winner")
+    });
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_EXTENSION);
+}
+
+#[test]
+fn test_extension_update() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let tx = env.gen_transfer_tx_with(&mut context, with_extensions, |nft| {
+        set_extension(nft, EXTENSION_DESCRIPTION, b"a fat cat");
+        nft.extensions.remove(0);
+    });
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_extension_update_attributes() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let tx = env.gen_transfer_tx_with(&mut context, with_extensions, |nft| {
+        set_extension(nft, EXTENSION_ATTRIBUTES, b"{\"color\":\"gold\"}")
+    });
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_EXTENSION);
+}
+
+#[test]
+fn test_extension_rename() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let tx = env.gen_transfer_tx_with(&mut context, with_extensions, |nft| {
+        rename(nft);
+        set_extension(nft, EXTENSION_IMAGE, b"ipfs://tom.png");
+    });
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_extension_rename_attributes() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let tx = env.gen_transfer_tx_with(&mut context, with_extensions, |nft| {
+        rename(nft);
+        nft.extensions.pop();
+    });
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_EXTENSION);
+}
+
+#[test]
+fn test_extension_unordered() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let tx = env.gen_transfer_tx_with(
+        &mut context,
+        |nft| {
+            with_extensions(nft);
+            nft.extensions.reverse();
+        },
+        |_| {},
+    );
+
+    //记录没有按类型排列，cell data本身就不合法
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_INVALID_EXTENSION);
+}
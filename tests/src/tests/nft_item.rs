@@ -0,0 +1,539 @@
+use super::*;
+use ckb_testtool::context::Context;
+
+//cat_item合约的错误码，与contracts/cat_item/src/error.rs一致
+const ERR_ITEM_FORGED: i8 = 6;
+
+fn gen_config() -> Config {
+    Config {
+        threshold: 1,
+        //测试中不需要管理员签名
+        admin_lock_hashes: vec![vec![0u8; 32]],
+        ..Default::default()
+    }
+}
+
+fn collar() -> Item {
+    Item {
+        kind: ITEM_KIND_COLLAR,
+        bonus: Statistics {
+            hp: 60,
+            atk: 0,
+            def: 20,
+            lck: 0,
+        },
+    }
+}
+
+fn claws() -> Item {
+    Item {
+        kind: ITEM_KIND_CLAWS,
+        bonus: Statistics {
+            hp: 0,
+            atk: 80,
+            def: 0,
+            lck: 10,
+        },
+    }
+}
+
+fn potion() -> Item {
+    Item {
+        kind: ITEM_KIND_POTION,
+        bonus: Statistics {
+            hp: 0,
+            atk: 50,
+            def: 50,
+            lck: 0,
+        },
+    }
+}
+
+//装备的放置方式
+enum Equip {
+    CellDep,
+    //作为输入并原样返还
+    Input,
+    //作为输入并销毁
+    Burn,
+}
+
+fn item_output(env: &CatEnv, lock: &Script) -> CellOutput {
+    CellOutput::new_builder()
+        .capacity(500u64.pack())
+        .lock(lock.clone())
+        .type_(Some(env.item_type.clone()).pack())
+        .build()
+}
+
+//猫0带着装备和猫1战斗，装备属于item_lock(默认为猫0的lock)，apply表示计算结果时是否算上装备
+fn gen_tx_for_item_fight(
+    context: &mut Context,
+    env: &CatEnv,
+    items: &[Item],
+    equip: Equip,
+    item_lock: Option<&Script>,
+    apply: bool,
+) -> TransactionView {
+    let locks = [env.gen_lock(context), env.gen_lock(context)];
+    let lock_hash_0 = Vec::from(locks[0].calc_script_hash().as_slice());
+    let lock_hash_1 = Vec::from(locks[1].calc_script_hash().as_slice());
+    let item_lock = item_lock.unwrap_or(&locks[0]).clone();
+
+    //装备必须改变战斗结果，这样才能看出合约是否算上了装备
+    let (input_nft, (n, output_nft)) = loop {
+        let input_nft = vec![
+            NFTData::gen_random_nft(&lock_hash_0),
+            NFTData::gen_random_nft(&lock_hash_1),
+        ];
+        let mut equipped: Statistics = input_nft[0].hash.into();
+        for item in items {
+            equipped.equip(&item.bonus);
+        }
+        let with_items = gen_fight_result_with_stats(
+            &input_nft,
+            &lock_hash_0,
+            &lock_hash_1,
+            3000,
+            &env.params,
+            [equipped, input_nft[1].hash.into()],
+        );
+        let without_items =
+            gen_fight_result(&input_nft, &lock_hash_0, &lock_hash_1, 3000, &env.params);
+        if let (Some(with_items), Some(without_items)) = (with_items, without_items) {
+            if with_items != without_items {
+                let result = if apply { with_items } else { without_items };
+                break (input_nft, result);
+            }
+        }
+    };
+
+    let mut cell_deps = env.cell_deps.clone();
+    let mut inputs = vec![
+        env.create_cat(context, &locks[0], &input_nft[0]),
+        env.create_cat(context, &locks[1], &input_nft[1]),
+    ];
+    let mut outputs = vec![env.cat_output(&locks[0]), env.cat_output(&locks[1])];
+    let mut outputs_data: Vec<Bytes> = output_nft
+        .iter()
+        .map(|nft| Bytes::from(nft.serialize().to_vec()))
+        .collect();
+    let mut witnesses = vec![
+        Bytes::new(),
+        WitnessArgsBuilder::default()
+            .input_type(Some(Bytes::from(n.to_be_bytes().to_vec())).pack())
+            .build()
+            .as_bytes(),
+    ];
+    for item in items {
+        let data = Bytes::from(item.serialize());
+        let out_point = context.create_cell(item_output(env, &item_lock), data.clone());
+        match equip {
+            Equip::CellDep => {
+                cell_deps.push(CellDep::new_builder().out_point(out_point).build());
+            }
+            Equip::Input => {
+                inputs.push(CellInput::new_builder().previous_output(out_point).build());
+                outputs.push(item_output(env, &item_lock));
+                outputs_data.push(data);
+                witnesses.push(Bytes::new());
+            }
+            Equip::Burn => {
+                inputs.push(CellInput::new_builder().previous_output(out_point).build());
+                witnesses.push(Bytes::new());
+            }
+        }
+    }
+
+    let tx = TransactionBuilder::default()
+        .inputs(inputs)
+        .outputs(outputs)
+        .outputs_data(outputs_data.pack())
+        .cell_deps(cell_deps)
+        .witnesses(witnesses.pack())
+        .build();
+    context.complete_tx(tx)
+}
+
+//双方的猫属于同一个lock，猫0喝掉一瓶药水，assigned是药水见证中指定的猫的位置
+fn gen_tx_for_shared_lock_potion(
+    context: &mut Context,
+    env: &CatEnv,
+    assigned: Option<u8>,
+) -> TransactionView {
+    let lock = env.gen_lock(context);
+    let lock_hash = Vec::from(lock.calc_script_hash().as_slice());
+
+    //药水必须改变战斗结果
+    let (input_nft, (n, output_nft)) = loop {
+        let input_nft = vec![
+            NFTData::gen_random_nft(&lock_hash),
+            NFTData::gen_random_nft(&lock_hash),
+        ];
+        let mut drunk: Statistics = input_nft[0].hash.into();
+        drunk.equip(&potion().bonus);
+        let with_potion = gen_fight_result_with_stats(
+            &input_nft,
+            &lock_hash,
+            &lock_hash,
+            3000,
+            &env.params,
+            [drunk, input_nft[1].hash.into()],
+        );
+        let without_potion =
+            gen_fight_result(&input_nft, &lock_hash, &lock_hash, 3000, &env.params);
+        if let (Some(with_potion), Some(without_potion)) = (with_potion, without_potion) {
+            if with_potion != without_potion {
+                break (input_nft, with_potion);
+            }
+        }
+    };
+
+    let potion_out_point =
+        context.create_cell(item_output(env, &lock), Bytes::from(potion().serialize()));
+    let inputs = vec![
+        env.create_cat(context, &lock, &input_nft[0]),
+        env.create_cat(context, &lock, &input_nft[1]),
+        CellInput::new_builder()
+            .previous_output(potion_out_point)
+            .build(),
+    ];
+    let outputs = vec![env.cat_output(&lock), env.cat_output(&lock)];
+    let outputs_data: Vec<Bytes> = output_nft
+        .iter()
+        .map(|nft| Bytes::from(nft.serialize().to_vec()))
+        .collect();
+    let potion_witness = match assigned {
+        Some(index) => WitnessArgsBuilder::default()
+            .input_type(Some(Bytes::from(vec![index])).pack())
+            .build()
+            .as_bytes(),
+        None => Bytes::new(),
+    };
+    let witnesses = vec![
+        Bytes::new(),
+        WitnessArgsBuilder::default()
+            .input_type(Some(Bytes::from(n.to_be_bytes().to_vec())).pack())
+            .build()
+            .as_bytes(),
+        potion_witness,
+    ];
+    env.build_tx(context, inputs, outputs, outputs_data, witnesses)
+}
+
+//转移一件装备，modify可以修改输出的装备，owner表示是否由Owner发起
+fn gen_tx_for_item_transfer<F: Fn(&mut Item)>(
+    context: &mut Context,
+    env: &CatEnv,
+    owner: bool,
+    modify: F,
+) -> TransactionView {
+    let lock = env.gen_lock(context);
+    let new_lock = env.gen_lock(context);
+    let input_item = claws();
+    let mut output_item = claws();
+    modify(&mut output_item);
+
+    let out_point =
+        context.create_cell(item_output(env, &lock), Bytes::from(input_item.serialize()));
+    let mut inputs = vec![CellInput::new_builder().previous_output(out_point).build()];
+    let mut witnesses = vec![Bytes::new()];
+    if owner {
+        let owner_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(env.owner_lock.clone())
+                .build(),
+            Bytes::new(),
+        );
+        inputs.push(
+            CellInput::new_builder()
+                .previous_output(owner_out_point)
+                .build(),
+        );
+        witnesses.push(Bytes::new());
+    }
+    let outputs = vec![item_output(env, &new_lock)];
+    let outputs_data = vec![Bytes::from(output_item.serialize())];
+    env.build_tx(context, inputs, outputs, outputs_data, witnesses)
+}
+
+//非Owner销毁一件装备，找零给原来的lock
+fn gen_tx_for_item_burn(context: &mut Context, env: &CatEnv) -> TransactionView {
+    let lock = env.gen_lock(context);
+    let out_point = context.create_cell(item_output(env, &lock), Bytes::from(claws().serialize()));
+    let inputs = vec![CellInput::new_builder().previous_output(out_point).build()];
+    let outputs = vec![CellOutput::new_builder()
+        .capacity(500u64.pack())
+        .lock(lock)
+        .build()];
+    env.build_tx(
+        context,
+        inputs,
+        outputs,
+        vec![Bytes::new()],
+        vec![Bytes::new()],
+    )
+}
+
+#[test]
+fn test_item_as_cell_dep() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config());
+    let tx = gen_tx_for_item_fight(
+        &mut context,
+        &env,
+        &[collar(), claws()],
+        Equip::CellDep,
+        None,
+        true,
+    );
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_item_as_input() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config());
+    let tx = gen_tx_for_item_fight(&mut context, &env, &[claws()], Equip::Input, None, true);
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_item_bonus_ignored() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config());
+    let tx = gen_tx_for_item_fight(&mut context, &env, &[claws()], Equip::CellDep, None, false);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_RESULT);
+}
+
+#[test]
+fn test_item_invalid_kind() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config());
+    //种类9不存在，cell dep中的装备不经过cat_item检查
+    let item = Item { kind: 9, ..claws() };
+    let tx = gen_tx_for_item_fight(&mut context, &env, &[item], Equip::CellDep, None, true);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_INVALID_ITEM);
+}
+
+#[test]
+fn test_item_of_other_lock() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config());
+    let stranger = env.gen_lock(&mut context);
+    //别人的装备不生效
+    let tx = gen_tx_for_item_fight(
+        &mut context,
+        &env,
+        &[claws()],
+        Equip::CellDep,
+        Some(&stranger),
+        false,
+    );
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_item_duplicate_kind() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config());
+    //同一种装备只能带一件
+    let tx = gen_tx_for_item_fight(
+        &mut context,
+        &env,
+        &[claws(), claws()],
+        Equip::CellDep,
+        None,
+        true,
+    );
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_DUPLICATE_ITEM);
+}
+
+#[test]
+fn test_item_transfer() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config());
+    let tx = gen_tx_for_item_transfer(&mut context, &env, false, |_| {});
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_item_forged() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config());
+    let tx = gen_tx_for_item_transfer(&mut context, &env, false, |item| item.bonus.atk = 100);
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_ITEM_FORGED);
+
+    //Owner可以修改装备
+    let tx = gen_tx_for_item_transfer(&mut context, &env, true, |item| item.bonus.atk = 100);
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_item_by_non_owner() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config());
+    //非Owner可以转移和销毁装备
+    let tx = gen_tx_for_item_transfer(&mut context, &env, false, |_| {});
+    context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    let tx = gen_tx_for_item_burn(&mut context, &env);
+    context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+
+    //但不能修改种类和属性
+    let tx = gen_tx_for_item_transfer(&mut context, &env, false, |item| {
+        item.kind = ITEM_KIND_ARMOUR
+    });
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_ITEM_FORGED);
+    let tx = gen_tx_for_item_transfer(&mut context, &env, false, |item| item.bonus.lck += 1);
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_ITEM_FORGED);
+}
+
+#[test]
+fn test_potion_burned() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config());
+    let tx = gen_tx_for_item_fight(&mut context, &env, &[potion()], Equip::Burn, None, true);
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_potion_with_equipment() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config());
+    //装备可以销毁，和药水一起生效
+    let tx = gen_tx_for_item_fight(
+        &mut context,
+        &env,
+        &[collar(), potion()],
+        Equip::Burn,
+        None,
+        true,
+    );
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_potion_not_burned() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config());
+    let tx = gen_tx_for_item_fight(&mut context, &env, &[potion()], Equip::Input, None, true);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_POTION_NOT_BURNED);
+}
+
+#[test]
+fn test_potion_burned_with_other_potion_moved() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config());
+    let tx = gen_tx_for_item_fight(&mut context, &env, &[potion()], Equip::Burn, None, true);
+    //同一个交易中顺便转移别人一瓶一样的药水，不算没有销毁
+    let stranger = env.gen_lock(&mut context);
+    let data = Bytes::from(potion().serialize());
+    let out_point = context.create_cell(item_output(&env, &stranger), data.clone());
+    let tx = tx
+        .as_advanced_builder()
+        .input(CellInput::new_builder().previous_output(out_point).build())
+        .output(item_output(&env, &env.gen_lock(&mut context)))
+        .output_data(data.pack())
+        .witness(Bytes::new().pack())
+        .build();
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_potion_as_cell_dep() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config());
+    //没有被消耗的药水不生效
+    let tx = gen_tx_for_item_fight(&mut context, &env, &[potion()], Equip::CellDep, None, true);
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_RESULT);
+
+    let tx = gen_tx_for_item_fight(&mut context, &env, &[potion()], Equip::CellDep, None, false);
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_potion_of_other_lock() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config());
+    let stranger = env.gen_lock(&mut context);
+    let tx = gen_tx_for_item_fight(
+        &mut context,
+        &env,
+        &[potion()],
+        Equip::Burn,
+        Some(&stranger),
+        true,
+    );
+
+    //别人的药水不生效
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_RESULT);
+}
+
+#[test]
+fn test_potion_shared_lock() {
+    let mut context = Context::default();
+    let env = CatEnv::with_items(&mut context, &gen_config());
+    //双方是同一个lock，药水在见证中指定给猫0，只有猫0加上药水
+    let tx = gen_tx_for_shared_lock_potion(&mut context, &env, Some(0));
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+
+    //没有指定时不知道给哪只猫喝
+    let tx = gen_tx_for_shared_lock_potion(&mut context, &env, None);
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_UNASSIGNED_POTION);
+
+    let tx = gen_tx_for_shared_lock_potion(&mut context, &env, Some(2));
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_UNASSIGNED_POTION);
+}
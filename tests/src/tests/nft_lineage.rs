@@ -0,0 +1,68 @@
+use super::*;
+use ckb_testtool::context::Context;
+
+fn gen_config() -> Config {
+    Config {
+        threshold: 1,
+        //测试中不需要管理员签名
+        admin_lock_hashes: vec![vec![0u8; 32]],
+        ..Default::default()
+    }
+}
+
+//已经输过几次的猫
+fn descendant(nft: &mut NFTData) {
+    nft.generation = 3;
+    nft.prev_hash = [7u8; 20];
+}
+
+#[test]
+fn test_lineage_fight() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let tx = env.gen_fight_tx_with(&mut context, descendant, |nft| {
+        if nft.win_streak > 0 {
+            assert_eq!((nft.generation, nft.prev_hash), (3, [7u8; 20]));
+        } else {
+            assert_eq!(nft.generation, 4);
+        }
+    });
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_lineage_not_updated() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    //输的一方没有记录上一代的hash
+    let tx = env.gen_fight_tx_with(&mut context, descendant, |nft| nft.prev_hash = [7u8; 20]);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_RESULT);
+}
+
+#[test]
+fn test_lineage_winner_changed() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let tx = env.gen_fight_tx_with(&mut context, descendant, |nft| {
+        if nft.win_streak > 0 {
+            nft.generation += 1;
+        }
+    });
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_RESULT);
+}
+
+#[test]
+fn test_lineage_forged_by_transfer() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let tx = env.gen_transfer_tx(&mut context, |nft| nft.prev_hash = [1u8; 20]);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_TRANSFER);
+}
@@ -0,0 +1,159 @@
+use super::*;
+use ckb_standalone_debugger::transaction::{
+    MockCellDep, MockInfo, MockInput, MockTransaction, ReprMockTransaction,
+};
+use ckb_testtool::context::Context;
+use ckb_tool::ckb_types::{core::TransactionView, packed::*};
+use serde_json::json;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+//本机版本的合约由make sim编译，路径通过这个环境变量传入
+const SIM_BIN_VAR: &str = "SOURLY_CAT_SIM_BIN";
+
+//测试程序在<target>/debug/deps中，这样CARGO_TARGET_DIR和--target-dir都能找到
+fn target_dir() -> PathBuf {
+    let exe = env::current_exe().expect("test executable");
+    exe.ancestors().nth(3).expect("target dir").to_path_buf()
+}
+
+//导出的交易和运行设置，可以在调试器中重放
+fn dump_dir() -> PathBuf {
+    target_dir().join("sourly_cat_sim")
+}
+
+//没有设置环境变量时跳过本机版本的运行；设置了但文件不存在是配置错误
+fn sim_bin() -> Option<PathBuf> {
+    let path = PathBuf::from(env::var_os(SIM_BIN_VAR)?);
+    assert!(
+        path.is_file(),
+        "{} is set but {} does not exist, run `make sim`",
+        SIM_BIN_VAR,
+        path.display()
+    );
+    Some(path)
+}
+
+fn gen_config() -> Config {
+    Config {
+        threshold: 1,
+        //测试中不需要管理员签名
+        admin_lock_hashes: vec![vec![0u8; 32]],
+        ..Default::default()
+    }
+}
+
+//ckb-x64-simulator读取的交易，带上所有输入和cell dep的内容
+fn mock_tx(context: &Context, tx: &TransactionView) -> MockTransaction {
+    let cell = |out_point: &OutPoint| context.cells.get(out_point).cloned().expect("cell");
+    let inputs = tx
+        .inputs()
+        .into_iter()
+        .map(|input| {
+            let (output, data) = cell(&input.previous_output());
+            MockInput {
+                input,
+                output,
+                data,
+                header: None,
+            }
+        })
+        .collect();
+    let cell_deps = tx
+        .cell_deps()
+        .into_iter()
+        .map(|cell_dep| {
+            let (output, data) = cell(&cell_dep.out_point());
+            MockCellDep {
+                cell_dep,
+                output,
+                data,
+                header: None,
+            }
+        })
+        .collect();
+    MockTransaction {
+        mock_info: MockInfo {
+            inputs,
+            cell_deps,
+            header_deps: vec![],
+        },
+        tx: tx.data(),
+    }
+}
+
+//导出交易，用本机版本的合约运行输入0的type script，返回退出码
+pub fn run_native(sim_bin: &Path, context: &Context, tx: &TransactionView, name: &str) -> i32 {
+    let dump_dir = dump_dir();
+    fs::create_dir_all(&dump_dir).expect("create dump dir");
+    let tx_file = dump_dir.join(format!("{}.json", name));
+    let repr: ReprMockTransaction = mock_tx(context, tx).into();
+    fs::write(&tx_file, serde_json::to_string_pretty(&repr).unwrap()).expect("write tx");
+    let setup_file = dump_dir.join(format!("{}.setup.json", name));
+    let setup = json!({
+        "is_lock_script": false,
+        "is_output": false,
+        "script_index": 0,
+        "native_binaries": {},
+    });
+    fs::write(&setup_file, setup.to_string()).expect("write setup");
+
+    let status = Command::new(sim_bin)
+        .env("CKB_TX_FILE", &tx_file)
+        .env("CKB_RUNNING_SETUP", &setup_file)
+        .status()
+        .expect("run sourly_cat_sim");
+    status.code().expect("exit code")
+}
+
+//同一个交易在本机版本和虚拟机中的结果一致
+fn assert_same_result(context: &mut Context, tx: &TransactionView, name: &str, code: i8) {
+    let verified = context.verify_tx(tx, MAX_CYCLES);
+    if code == 0 {
+        verified.expect("pass verification");
+    } else {
+        assert_script_error(verified, code);
+    }
+    match sim_bin() {
+        Some(sim_bin) => assert_eq!(run_native(&sim_bin, context, tx, name), code as i32),
+        None => println!(
+            "skip the native run of {}: set {} to the sourly_cat_sim binary built by `make sim`",
+            name, SIM_BIN_VAR
+        ),
+    }
+}
+
+#[test]
+fn test_native_fight() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let tx = env.gen_fight_tx(&mut context);
+    assert_same_result(&mut context, &tx, "fight", 0);
+}
+
+#[test]
+fn test_native_fight_wrong_result() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let tx = env.gen_fight_tx_with(&mut context, |_| {}, |nft| nft.fishes += 1);
+    assert_same_result(&mut context, &tx, "fight_wrong_result", ERR_WRONG_RESULT);
+}
+
+#[test]
+fn test_native_transfer() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let tx = env.gen_transfer_tx(&mut context, |_| {});
+    assert_same_result(&mut context, &tx, "transfer", 0);
+}
+
+#[test]
+fn test_native_wrong_transfer() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let tx = env.gen_transfer_tx(&mut context, |nft| nft.fishes += 1);
+    assert_same_result(&mut context, &tx, "wrong_transfer", ERR_WRONG_TRANSFER);
+}
@@ -0,0 +1,59 @@
+use super::*;
+use ckb_testtool::context::Context;
+
+fn gen_config(flags: u8) -> Config {
+    Config {
+        threshold: 1,
+        //测试中不需要管理员签名
+        admin_lock_hashes: vec![vec![0u8; 32]],
+        flags,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_fighting_with_config() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    let tx = env.gen_fight_tx(&mut context);
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_paused_fighting() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(CONFIG_FLAG_PAUSED));
+    let tx = env.gen_fight_tx(&mut context);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_PAUSED);
+}
+
+#[test]
+fn test_paused_rename() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(CONFIG_FLAG_PAUSED));
+    let tx = env.gen_transfer_tx(&mut context, |nft| {
+        nft.name = *b"Tom\0\0\0\0\0\0\0\0\0\0\0\0\0";
+        nft.fishes -= DEFAULT_RENAME_FEE;
+    });
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_PAUSED);
+}
+
+#[test]
+fn test_paused_transfer() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(CONFIG_FLAG_PAUSED));
+    let tx = env.gen_transfer_tx(&mut context, |_| {});
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
@@ -0,0 +1,193 @@
+use super::*;
+use ckb_testtool::context::Context;
+use proptest::prelude::*;
+
+//每个用例都要跑一次合约，用例数不宜太多
+const CASES: u32 = 64;
+//NFTData中可以单独修改的字段数，见mutate
+const FIELD_COUNT: usize = 11;
+
+fn gen_config(params: Params) -> Config {
+    Config {
+        threshold: 1,
+        //测试中不需要管理员签名
+        admin_lock_hashes: vec![vec![0u8; 32]],
+        params: Some(params),
+        ..Default::default()
+    }
+}
+
+//def_base不小于lck_factor*100，保证伤害公式的除数大于0；
+//def_base正好等于lck_factor*100时，幸运值为100的一方不会受到伤害
+fn params_strategy() -> impl Strategy<Value = Params> {
+    (
+        0u8..=3,
+        prop_oneof![Just(0u16), 0u16..=50],
+        1u8..=10,
+        1u8..=20,
+        1u8..=20,
+        prop_oneof![Just(999), -1000i32..1000],
+    )
+        .prop_map(
+            |(lck_factor, extra, hp_factor, loser_divisor, winner_divisor, hidden_reward)| Params {
+                def_base: lck_factor as u16 * 100 + extra,
+                lck_factor,
+                hp_factor,
+                loser_divisor,
+                winner_divisor,
+                hidden_reward,
+            },
+        )
+}
+
+//hash的最后一个字节决定幸运值，经常取99(幸运值100)以覆盖零伤害
+fn hash_strategy() -> impl Strategy<Value = [u8; 20]> {
+    (any::<[u8; 20]>(), prop_oneof![Just(99u8), any::<u8>()]).prop_map(|(mut hash, lck)| {
+        hash[19] = lck;
+        hash
+    })
+}
+
+//fish较小时容易触发隐藏奖励
+fn fishes_strategy() -> impl Strategy<Value = i32> {
+    prop_oneof![0i32..20, 0i32..1_000_000]
+}
+
+//与合约一致：n回合能否确定胜负，返回赢的一方
+fn expected_winner(n: u16, stats: &[Statistics; 2], params: &Params) -> Option<usize> {
+    let hurts = [
+        params.hurt(&stats[0], &stats[1]).expect("hurt"),
+        params.hurt(&stats[1], &stats[0]).expect("hurt"),
+    ];
+    params.round_winner(n, &hurts, stats)
+}
+
+//修改一个字段，结果一定与原来不同
+fn mutate(nft: &mut NFTData, field: usize) {
+    match field {
+        0 => nft.name[0] ^= 1,
+        1 => nft.hash[0] ^= 1,
+        2 => nft.fishes = nft.fishes.wrapping_add(1),
+        3 => nft.season = nft.season.wrapping_add(1),
+        4 => nft.last_season_fishes = nft.last_season_fishes.wrapping_add(1),
+        5 => nft.badges ^= 0x8000,
+        6 => nft.win_streak = nft.win_streak.wrapping_add(1),
+        7 => nft.wins = nft.wins.wrapping_add(1),
+        8 => nft.losses = nft.losses.wrapping_add(1),
+        9 => nft.generation = nft.generation.wrapping_add(1),
+        _ => nft.prev_hash[0] ^= 1,
+    }
+}
+
+struct Fight {
+    env: CatEnv,
+    locks: [Script; 2],
+    input_nft: Vec<NFTData>,
+    stats: [Statistics; 2],
+    //按最小回合数算出的结果，平局时为None
+    result: Option<(u16, Vec<NFTData>)>,
+}
+
+fn gen_fight(
+    context: &mut Context,
+    params: &Params,
+    hashes: [[u8; 20]; 2],
+    fishes: [i32; 2],
+) -> Fight {
+    let env = CatEnv::new(context, &gen_config(params.clone()));
+    let locks = [env.gen_lock(context), env.gen_lock(context)];
+    let lock_hash_0 = Vec::from(locks[0].calc_script_hash().as_slice());
+    let lock_hash_1 = Vec::from(locks[1].calc_script_hash().as_slice());
+    let mut input_nft = vec![
+        NFTData::gen_random_nft(&lock_hash_0),
+        NFTData::gen_random_nft(&lock_hash_1),
+    ];
+    for i in 0..2 {
+        input_nft[i].hash = hashes[i];
+        input_nft[i].fishes = fishes[i];
+    }
+    let stats = [hashes[0].into(), hashes[1].into()];
+    let result = gen_fight_result(
+        &input_nft,
+        &lock_hash_0,
+        &lock_hash_1,
+        u16::max_value(),
+        params,
+    );
+    Fight {
+        env,
+        locks,
+        input_nft,
+        stats,
+        result,
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    //任意回合数：能确定胜负时合约接受正确的结果，否则拒绝
+    #[test]
+    fn prop_fight_any_round_count(
+        params in params_strategy(),
+        hashes in (hash_strategy(), hash_strategy()),
+        fishes in (fishes_strategy(), fishes_strategy()),
+        n in 1u16..3000,
+    ) {
+        let mut context = Context::default();
+        let fight = gen_fight(&mut context, &params, [hashes.0, hashes.1], [fishes.0, fishes.1]);
+        //结果与回合数无关，平局时原样输出
+        let output_nft = match &fight.result {
+            Some((_, output_nft)) => output_nft.clone(),
+            None => fight.input_nft.clone(),
+        };
+        let tx = fight.env.build_fight_tx(
+            &mut context,
+            &fight.locks,
+            &fight.input_nft,
+            &output_nft,
+            n,
+        );
+        let verified = context.verify_tx(&tx, MAX_CYCLES);
+        match expected_winner(n, &fight.stats, &params) {
+            Some(_) => prop_assert!(verified.is_ok(), "{}", verified.unwrap_err()),
+            None => assert_script_error(verified, ERR_WRONG_RESULT),
+        }
+    }
+
+    //正确的结果被接受，修改输出中任意一个字段都会被拒绝
+    #[test]
+    fn prop_fight_single_field_mutation(
+        params in params_strategy(),
+        hashes in (hash_strategy(), hash_strategy()),
+        fishes in (fishes_strategy(), fishes_strategy()),
+        side in 0usize..2,
+        field in 0usize..FIELD_COUNT,
+    ) {
+        let mut context = Context::default();
+        let fight = gen_fight(&mut context, &params, [hashes.0, hashes.1], [fishes.0, fishes.1]);
+        prop_assume!(fight.result.is_some());
+        let (n, output_nft) = fight.result.clone().unwrap();
+
+        let tx = fight.env.build_fight_tx(
+            &mut context,
+            &fight.locks,
+            &fight.input_nft,
+            &output_nft,
+            n,
+        );
+        let verified = context.verify_tx(&tx, MAX_CYCLES);
+        prop_assert!(verified.is_ok(), "{}", verified.unwrap_err());
+
+        let mut mutated = output_nft;
+        mutate(&mut mutated[side], field);
+        let tx = fight.env.build_fight_tx(
+            &mut context,
+            &fight.locks,
+            &fight.input_nft,
+            &mutated,
+            n,
+        );
+        prop_assert!(context.verify_tx(&tx, MAX_CYCLES).is_err());
+    }
+}
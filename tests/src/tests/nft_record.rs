@@ -0,0 +1,71 @@
+use super::*;
+use ckb_testtool::context::Context;
+
+fn gen_config() -> Config {
+    Config {
+        threshold: 1,
+        //测试中不需要管理员签名
+        admin_lock_hashes: vec![vec![0u8; 32]],
+        ..Default::default()
+    }
+}
+
+//已经打过几场的猫
+fn veteran(nft: &mut NFTData) {
+    nft.badges = BADGE_FIRST_WIN;
+    nft.win_streak = 1;
+    nft.wins = 5;
+    nft.losses = 2;
+}
+
+#[test]
+fn test_record_fight() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let tx = env.gen_fight_tx_with(&mut context, veteran, |nft| {
+        if nft.win_streak > 0 {
+            assert_eq!((nft.win_streak, nft.wins, nft.losses), (2, 6, 2));
+        } else {
+            assert_eq!((nft.win_streak, nft.wins, nft.losses), (0, 5, 3));
+        }
+    });
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_record_not_updated() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    //输的一方没有记录负场
+    let tx = env.gen_fight_tx_with(&mut context, veteran, |nft| nft.losses = 2);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_RESULT);
+}
+
+#[test]
+fn test_record_streak_not_reset() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    //输了之后连胜次数必须清零
+    let tx = env.gen_fight_tx_with(&mut context, veteran, |nft| {
+        if nft.win_streak == 0 {
+            nft.win_streak = 1;
+        }
+    });
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_RESULT);
+}
+
+#[test]
+fn test_record_forged_by_transfer() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let tx = env.gen_transfer_tx(&mut context, |nft| nft.wins += 1);
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_TRANSFER);
+}
@@ -0,0 +1,191 @@
+use super::*;
+use ckb_crypto::secp::Generator;
+use ckb_testtool::context::Context;
+use ckb_tool::ckb_types::{
+    bytes::Bytes,
+    core::{TransactionBuilder, TransactionView},
+    packed::*,
+    prelude::*,
+};
+
+fn gen_config() -> Config {
+    Config {
+        threshold: 1,
+        //测试中不需要管理员签名
+        admin_lock_hashes: vec![vec![0u8; 32]],
+        ..Default::default()
+    }
+}
+
+//用pw-lock锁住的猫改名，output_lock_args与lock_args不同时表示同时转给别人
+fn gen_tx_for_nft_rename(
+    context: &mut Context,
+    lock_args: Bytes,
+    output_lock_args: Bytes,
+    new_name: &[u8],
+    fee: i32,
+) -> TransactionView {
+    let env = CatEnv::new(context, &gen_config());
+    let sighash_all_out_point = context.deploy_cell(KECCAK256_ALL_ACPL_BIN.clone());
+    let secp_out_point = context.deploy_cell(SECP256K1_DATA_BIN.clone());
+
+    // prepare scripts
+    let lock_script = context
+        .build_script(&sighash_all_out_point, lock_args)
+        .expect("lock script");
+    let output_lock_script = context
+        .build_script(&sighash_all_out_point, output_lock_args)
+        .expect("lock script");
+
+    let lock_hash = Vec::from(lock_script.calc_script_hash().as_slice());
+    let input_nft = NFTData::gen_random_nft(&lock_hash);
+
+    let mut output_nft = input_nft.clone();
+    output_nft.name = [0u8; 16];
+    output_nft.name[..new_name.len()].copy_from_slice(new_name);
+    output_nft.fishes = input_nft.fishes - fee;
+
+    let inputs = vec![env.create_cat(context, &lock_script, &input_nft)];
+    let outputs = vec![env.cat_output(&output_lock_script)];
+    let outputs_data = vec![Bytes::from(output_nft.serialize().to_vec())];
+
+    let witnesses = vec![WitnessArgsBuilder::default().build().as_bytes()];
+
+    // build transaction
+    let tx = TransactionBuilder::default()
+        .inputs(inputs)
+        .outputs(outputs)
+        .outputs_data(outputs_data.pack())
+        .cell_deps(env.cell_deps.clone())
+        .cell_dep(
+            CellDep::new_builder()
+                .out_point(sighash_all_out_point)
+                .build(),
+        )
+        .cell_dep(CellDep::new_builder().out_point(secp_out_point).build())
+        .witnesses(witnesses.pack())
+        .build();
+    context.complete_tx(tx)
+}
+
+#[test]
+fn test_nft_rename() {
+    for chain in Chain::all() {
+        // deploy contract
+        let mut context = Context::default();
+        let privkey = Generator::random_privkey();
+        let pubkey_hash = chain.pubkey_hash(&privkey);
+
+        let tx = gen_tx_for_nft_rename(
+            &mut context,
+            pubkey_hash.clone(),
+            pubkey_hash,
+            "橘猫 Tom".as_bytes(),
+            DEFAULT_RENAME_FEE,
+        );
+        let tx = sign_tx_keccak256(&mut context, tx, &privkey, chain);
+
+        // run
+        let cycles = context
+            .verify_tx(&tx, MAX_CYCLES)
+            .unwrap_or_else(|err| panic!("{:?}: {}", chain, err));
+        println!("{:?} consume cycles: {}", chain, cycles);
+    }
+}
+
+#[test]
+fn test_nft_rename_without_fee() {
+    for chain in Chain::all() {
+        let mut context = Context::default();
+        let privkey = Generator::random_privkey();
+        let pubkey_hash = chain.pubkey_hash(&privkey);
+
+        let tx = gen_tx_for_nft_rename(&mut context, pubkey_hash.clone(), pubkey_hash, b"Tom", 0);
+        let tx = sign_tx_keccak256(&mut context, tx, &privkey, chain);
+
+        assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_RENAME);
+    }
+}
+
+#[test]
+fn test_nft_rename_wrong_fee() {
+    for chain in Chain::all() {
+        let mut context = Context::default();
+        let privkey = Generator::random_privkey();
+        let pubkey_hash = chain.pubkey_hash(&privkey);
+
+        //多扣了改名费也不行
+        let tx = gen_tx_for_nft_rename(
+            &mut context,
+            pubkey_hash.clone(),
+            pubkey_hash,
+            b"Tom",
+            DEFAULT_RENAME_FEE + 1,
+        );
+        let tx = sign_tx_keccak256(&mut context, tx, &privkey, chain);
+
+        assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_RENAME);
+    }
+}
+
+#[test]
+fn test_nft_rename_invalid_name() {
+    for chain in Chain::all() {
+        let mut context = Context::default();
+        let privkey = Generator::random_privkey();
+        let pubkey_hash = chain.pubkey_hash(&privkey);
+
+        let tx = gen_tx_for_nft_rename(
+            &mut context,
+            pubkey_hash.clone(),
+            pubkey_hash,
+            b"Tom\n",
+            DEFAULT_RENAME_FEE,
+        );
+        let tx = sign_tx_keccak256(&mut context, tx, &privkey, chain);
+
+        assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_INVALID_NAME);
+    }
+}
+
+#[test]
+fn test_nft_rename_unsigned() {
+    for chain in Chain::all() {
+        let mut context = Context::default();
+        let privkey = Generator::random_privkey();
+        let pubkey_hash = chain.pubkey_hash(&privkey);
+
+        //不签名，anyone-can-pay lock 不能用来改名
+        let tx = gen_tx_for_nft_rename(
+            &mut context,
+            pubkey_hash.clone(),
+            pubkey_hash,
+            b"Tom",
+            DEFAULT_RENAME_FEE,
+        );
+
+        assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_RENAME);
+    }
+}
+
+#[test]
+fn test_nft_rename_by_new_owner() {
+    for chain in Chain::all() {
+        let mut context = Context::default();
+        let privkey = Generator::random_privkey();
+        let pubkey_hash = chain.pubkey_hash(&privkey);
+        let new_owner = chain.pubkey_hash(&Generator::random_privkey());
+
+        //改名的同时转给别人，等于由新主人改名
+        let tx = gen_tx_for_nft_rename(
+            &mut context,
+            pubkey_hash,
+            new_owner,
+            b"Tom",
+            DEFAULT_RENAME_FEE,
+        );
+        let tx = sign_tx_keccak256(&mut context, tx, &privkey, chain);
+
+        assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_RENAME);
+    }
+}
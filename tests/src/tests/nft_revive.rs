@@ -0,0 +1,175 @@
+use super::*;
+use ckb_testtool::context::Context;
+
+const REVIVE_FEE: u64 = 1000;
+const REVIVE_FISHES: i32 = 20;
+
+fn gen_config(flags: u8) -> Config {
+    Config {
+        threshold: 1,
+        //测试中不需要管理员签名
+        admin_lock_hashes: vec![vec![0u8; 32]],
+        flags,
+        revive: Some(Revive {
+            fee: REVIVE_FEE,
+            fishes: REVIVE_FISHES,
+        }),
+        ..Default::default()
+    }
+}
+
+//主人复活一只fish为负数的猫，paid是支付给配置cell的lock的费用
+fn gen_tx_for_revive(
+    context: &mut Context,
+    env: &CatEnv,
+    fishes: i32,
+    revived_fishes: i32,
+    paid: Option<u64>,
+) -> TransactionView {
+    gen_tx_for_revive_to(context, env, fishes, revived_fishes, paid, &env.owner_lock)
+}
+
+//同上，费用支付给fee_lock
+fn gen_tx_for_revive_to(
+    context: &mut Context,
+    env: &CatEnv,
+    fishes: i32,
+    revived_fishes: i32,
+    paid: Option<u64>,
+    fee_lock: &Script,
+) -> TransactionView {
+    let lock = env.gen_lock(context);
+    let lock_hash = Vec::from(lock.calc_script_hash().as_slice());
+    let mut input_nft = NFTData::gen_random_nft(&lock_hash);
+    input_nft.fishes = fishes;
+    let mut output_nft = input_nft.clone();
+    output_nft.fishes = revived_fishes;
+
+    let payment_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+    let inputs = vec![
+        env.create_cat(context, &lock, &input_nft),
+        CellInput::new_builder()
+            .previous_output(payment_out_point)
+            .build(),
+    ];
+    let mut outputs = vec![env.cat_output(&lock)];
+    let mut outputs_data = vec![Bytes::from(output_nft.serialize().to_vec())];
+    if let Some(paid) = paid {
+        outputs.push(
+            CellOutput::new_builder()
+                .capacity(paid.pack())
+                .lock(fee_lock.clone())
+                .build(),
+        );
+        outputs_data.push(Bytes::new());
+    }
+    //always success不检查签名，lock里放任意数据表示已签名
+    let witnesses = vec![
+        WitnessArgsBuilder::default()
+            .lock(Some(Bytes::from(vec![0u8; SIGNATURE_SIZE])).pack())
+            .build()
+            .as_bytes(),
+        Bytes::new(),
+    ];
+    env.build_tx(context, inputs, outputs, outputs_data, witnesses)
+}
+
+#[test]
+fn test_revive() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    let tx = gen_tx_for_revive(&mut context, &env, -5, REVIVE_FISHES, Some(REVIVE_FEE));
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_revive_fee_to_config_lock() {
+    let mut context = Context::default();
+    let mut env = CatEnv::new(&mut context, &gen_config(0));
+    //args中的Owner换成别的lock，与配置cell的lock不同
+    let args_owner = env.gen_lock(&mut context);
+    let mut args: Vec<u8> = env.type_script.args().unpack();
+    args[..32].copy_from_slice(args_owner.calc_script_hash().as_slice());
+    env.type_script = env
+        .type_script
+        .clone()
+        .as_builder()
+        .args(Bytes::from(args).pack())
+        .build();
+
+    //费用由管理员收取，付给配置cell的lock
+    let tx = gen_tx_for_revive(&mut context, &env, -5, REVIVE_FISHES, Some(REVIVE_FEE));
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+
+    let tx = gen_tx_for_revive_to(
+        &mut context,
+        &env,
+        -5,
+        REVIVE_FISHES,
+        Some(REVIVE_FEE),
+        &args_owner,
+    );
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_REVIVE);
+}
+
+#[test]
+fn test_revive_underpaid() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    let tx = gen_tx_for_revive(&mut context, &env, -5, REVIVE_FISHES, Some(REVIVE_FEE - 1));
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_REVIVE);
+
+    let tx = gen_tx_for_revive(&mut context, &env, -5, REVIVE_FISHES, None);
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_REVIVE);
+}
+
+#[test]
+fn test_revive_too_many_fishes() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    let tx = gen_tx_for_revive(&mut context, &env, -5, REVIVE_FISHES + 1, Some(REVIVE_FEE));
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_REVIVE);
+}
+
+#[test]
+fn test_revive_healthy_cat() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    //fish不是负数的猫不能复活
+    let tx = gen_tx_for_revive(&mut context, &env, 0, REVIVE_FISHES, Some(REVIVE_FEE));
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_TRANSFER);
+}
+
+#[test]
+fn test_revive_paused() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(CONFIG_FLAG_PAUSED));
+    let tx = gen_tx_for_revive(&mut context, &env, -5, REVIVE_FISHES, Some(REVIVE_FEE));
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_PAUSED);
+}
+
+#[test]
+fn test_knocked_out_fighting() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config(0));
+    let tx = env.gen_fight_tx_with(&mut context, |nft| nft.fishes = -1, |_| {});
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_KNOCKED_OUT);
+}
@@ -0,0 +1,114 @@
+use super::*;
+use ckb_testtool::context::Context;
+
+const CURRENT_SEASON: u32 = 2;
+
+fn gen_config() -> Config {
+    Config {
+        threshold: 1,
+        //测试中不需要管理员签名
+        admin_lock_hashes: vec![vec![0u8; 32]],
+        season: Some(Season {
+            number: CURRENT_SEASON,
+            base_fishes: 100,
+        }),
+        ..Default::default()
+    }
+}
+
+//上赛季的猫
+fn last_season(nft: &mut NFTData) {
+    nft.season = CURRENT_SEASON - 1;
+    nft.fishes = 250;
+}
+
+#[test]
+fn test_season_migration() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let tx = env.gen_fight_tx_with(&mut context, last_season, |nft| {
+        assert_eq!(nft.season, CURRENT_SEASON);
+        assert_eq!(nft.last_season_fishes, 250);
+    });
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_season_migration_skipped() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    //输出仍然是上赛季，没有存档
+    let tx = env.gen_fight_tx_with(&mut context, last_season, |nft| {
+        nft.season = CURRENT_SEASON - 1;
+        nft.last_season_fishes = 0;
+    });
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_RESULT);
+}
+
+#[test]
+fn test_season_from_future() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let tx = env.gen_fight_tx_with(&mut context, |nft| nft.season = CURRENT_SEASON + 1, |_| {});
+
+    assert_script_error(context.verify_tx(&tx, MAX_CYCLES), ERR_WRONG_SEASON);
+}
+
+#[test]
+fn test_season_revives_knocked_out() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    //上赛季已经不能战斗的猫，新赛季重置后可以继续
+    let tx = env.gen_fight_tx_with(
+        &mut context,
+        |nft| {
+            nft.season = CURRENT_SEASON - 1;
+            nft.fishes = -5;
+        },
+        |_| {},
+    );
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_season_legacy_transfer() {
+    let mut context = Context::default();
+    let env = CatEnv::new(&mut context, &gen_config());
+    let lock = env.gen_lock(&mut context);
+    let new_lock = env.gen_lock(&mut context);
+    let lock_hash = Vec::from(lock.calc_script_hash().as_slice());
+
+    //旧的40字节数据可以原样转账
+    let nft_data = Bytes::from(
+        NFTData::gen_random_nft(&lock_hash)
+            .serialize_legacy()
+            .to_vec(),
+    );
+    let out_point = context.create_cell(env.cat_output(&lock), nft_data.clone());
+    let inputs = vec![CellInput::new_builder().previous_output(out_point).build()];
+    let outputs = vec![env.cat_output(&new_lock)];
+    let tx = env.build_tx(
+        &mut context,
+        inputs,
+        outputs,
+        vec![nft_data],
+        vec![Bytes::new()],
+    );
+
+    // run
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
@@ -1,4 +1,4 @@
-use super::{hash::*, *};
+use super::*;
 use ckb_crypto::secp::Generator;
 use ckb_testtool::context::Context;
 use ckb_tool::ckb_types::{
@@ -159,28 +159,19 @@ fn gen_tx_for_nft_transfer(context: &mut Context, lock_args: Bytes) -> Transacti
 
 #[test]
 fn test_nft_transfer() {
-    // deploy contract
-    let mut context = Context::default();
-    let privkey = Generator::random_privkey();
-    let pubkey = privkey.pubkey().expect("pubkey");
-    let pubkey_hash =
-        if get_current_chain_id() == CHAIN_ID_BTC || get_current_chain_id() == CHAIN_ID_DOGE {
-            let pubkey = if is_compressed() {
-                pubkey_compressed(&pubkey)
-            } else {
-                pubkey_uncompressed(&pubkey)
-            };
-            ripemd_sha(&pubkey)
-        } else {
-            eth160(pubkey)
-        };
+    for chain in Chain::all() {
+        // deploy contract
+        let mut context = Context::default();
+        let privkey = Generator::random_privkey();
+        let pubkey_hash = chain.pubkey_hash(&privkey);
 
-    let tx = gen_tx_for_nft_transfer(&mut context, pubkey_hash);
+        let tx = gen_tx_for_nft_transfer(&mut context, pubkey_hash);
 
-    let tx = sign_tx_keccak256(&mut context, tx, &privkey);
-    // run
-    let cycles = context
-        .verify_tx(&tx, MAX_CYCLES)
-        .expect("pass verification");
-    println!("consume cycles: {}", cycles);
+        let tx = sign_tx_keccak256(&mut context, tx, &privkey, chain);
+        // run
+        let cycles = context
+            .verify_tx(&tx, MAX_CYCLES)
+            .unwrap_or_else(|err| panic!("{:?}: {}", chain, err));
+        println!("{:?} consume cycles: {}", chain, cycles);
+    }
 }
@@ -0,0 +1,413 @@
+use super::*;
+use ckb_testtool::{builtin::ALWAYS_SUCCESS, context::Context};
+use ckb_tool::ckb_types::{
+    bytes::Bytes,
+    core::{ScriptHashType, TransactionBuilder},
+    packed::*,
+    prelude::*,
+};
+use serde_json::{json, Value};
+use std::{env, fs};
+
+//战斗的测试向量，格式见README，其它语言的前端可以用它检查自己的计算
+const VECTORS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/vectors/fight_v2.json");
+const VECTORS_VERSION: u64 = 2;
+//设置后test_fight_vectors_up_to_date重新生成测试向量
+const UPDATE_VECTORS_VAR: &str = "UPDATE_FIGHT_VECTORS";
+
+//测试向量中的lock通过type hash引用这个cell里的always success，
+//lock hash只取决于lock args，不依赖always success的二进制
+const VECTOR_TYPE_ARGS: &[u8] = b"sourly_cat vectors";
+
+fn vector_type_script() -> Script {
+    Script::new_builder()
+        .code_hash([0u8; 32].pack())
+        .hash_type(ScriptHashType::Data.into())
+        .args(Bytes::from(VECTOR_TYPE_ARGS).pack())
+        .build()
+}
+
+fn vector_lock(args: &[u8]) -> Script {
+    Script::new_builder()
+        .code_hash(vector_type_script().calc_script_hash())
+        .hash_type(ScriptHashType::Type.into())
+        .args(Bytes::from(args.to_vec()).pack())
+        .build()
+}
+
+//cell dep中的脚本不会执行，所以这个type script不需要存在
+fn deploy_vector_lock(context: &mut Context) -> CellDep {
+    let out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .type_(Some(vector_type_script()).pack())
+            .build(),
+        ALWAYS_SUCCESS.clone(),
+    );
+    CellDep::new_builder().out_point(out_point).build()
+}
+
+//向量使用的赛季，输入可以是上一个赛季的猫
+fn vector_season() -> Season {
+    Season {
+        number: 1,
+        base_fishes: 100,
+    }
+}
+
+//一种情形：怎样准备双方的猫，以及什么样的结果属于这种情形
+struct Case {
+    name: &'static str,
+    count: usize,
+    //修改刚铸造的猫，stats是双方的属性
+    prepare: fn(&mut [NFTData], &[Statistics; 2], &Params),
+    //按迁移后的输入和战斗结果判断，不符合时换一对猫
+    accept: fn(&[NFTData], &[NFTData]) -> bool,
+    //合约拒绝这场战斗时的错误码
+    error: Option<i8>,
+}
+
+fn loser(inputs: &[NFTData], outputs: &[NFTData]) -> usize {
+    //输的一方hash改变
+    if outputs[0].hash == inputs[0].hash {
+        1
+    } else {
+        0
+    }
+}
+
+fn cases() -> Vec<Case> {
+    vec![
+        //刚铸造的猫
+        Case {
+            name: "fresh",
+            count: 40,
+            prepare: |_, _, _| {},
+            accept: |_, _| true,
+            error: None,
+        },
+        //输的一方fish恰好输光，得到隐藏奖励和徽章
+        Case {
+            name: "hidden_reward",
+            count: 10,
+            prepare: |nft, stats, params| {
+                for i in 0..2 {
+                    nft[i].fishes = stats[1 - i].atk as i32 / params.loser_divisor as i32;
+                }
+            },
+            accept: |inputs, outputs| {
+                outputs[loser(inputs, outputs)].badges & BADGE_HIDDEN_REWARD != 0
+            },
+            error: None,
+        },
+        //输的一方fish变成负数，被打倒并得到徽章
+        Case {
+            name: "knocked_out_by_fight",
+            count: 10,
+            prepare: |nft, _, _| nft.iter_mut().for_each(|nft| nft.fishes = 0),
+            accept: |inputs, outputs| outputs[loser(inputs, outputs)].fishes < 0,
+            error: None,
+        },
+        //被打倒的猫不能战斗，输出按照没有这条限制时的规则计算
+        Case {
+            name: "knocked_out_input",
+            count: 5,
+            prepare: |nft, _, _| nft[1].fishes = -1,
+            accept: |_, _| true,
+            error: Some(ERR_KNOCKED_OUT),
+        },
+        //上赛季的猫先迁移：存档fish并重置为新赛季的初始值
+        Case {
+            name: "season_migration",
+            count: 10,
+            prepare: |nft, stats, _| {
+                for i in 0..2 {
+                    nft[i].season = 0;
+                    nft[i].fishes = stats[i].hp as i32 * 3;
+                    nft[i].last_season_fishes = 50;
+                }
+            },
+            accept: |_, _| true,
+            error: None,
+        },
+        //上赛季被打倒的猫在新赛季可以重新战斗
+        Case {
+            name: "season_revival",
+            count: 5,
+            prepare: |nft, _, _| {
+                nft.iter_mut().for_each(|nft| {
+                    nft.season = 0;
+                    nft.fishes = -20;
+                })
+            },
+            accept: |_, _| true,
+            error: None,
+        },
+        //双方都已连胜两场，赢的一方达到连胜徽章，输的一方连胜清零
+        Case {
+            name: "win_streak",
+            count: 10,
+            prepare: |nft, _, _| {
+                nft.iter_mut().for_each(|nft| {
+                    nft.badges = BADGE_FIRST_WIN;
+                    nft.win_streak = 2;
+                    nft.wins = 2;
+                    nft.losses = 1;
+                })
+            },
+            accept: |_, _| true,
+            error: None,
+        },
+        //战绩已经到上限，徽章保持不变
+        Case {
+            name: "veteran",
+            count: 5,
+            prepare: |nft, _, _| {
+                nft.iter_mut().for_each(|nft| {
+                    nft.badges = BADGE_FIRST_WIN
+                        | BADGE_WIN_STREAK
+                        | BADGE_HIDDEN_REWARD
+                        | BADGE_NEGATIVE_SURVIVOR;
+                    nft.win_streak = u8::max_value();
+                    nft.wins = u32::max_value();
+                    nft.losses = u32::max_value();
+                    nft.generation = 7;
+                    nft.prev_hash = [7u8; 20];
+                })
+            },
+            accept: |_, _| true,
+            error: None,
+        },
+    ]
+}
+
+//按照铸造的规则生成当前赛季的猫，名字和lock只取决于种子，保证向量可以重复生成
+fn vector_cat(case: &str, k: usize, j: usize) -> (Vec<u8>, Vec<u8>, NFTData) {
+    let lock_args = blake2b_160(format!("sourly_cat vector {} {} {}", case, k, j)).to_vec();
+    let lock_hash = Vec::from(vector_lock(&lock_args).calc_script_hash().as_slice());
+    let name = format!("cat {}-{}", k, j);
+    let mut nft = NFTData::new();
+    nft.name[..name.len()].copy_from_slice(name.as_bytes());
+    let mut conc = name.as_bytes().to_vec();
+    conc.extend(lock_hash.iter());
+    nft.hash = blake2b_160(&conc);
+    nft.fishes = 100;
+    nft.season = vector_season().number;
+    (lock_args, lock_hash, nft)
+}
+
+fn gen_vectors(case: &Case, params: &Params) -> Vec<Value> {
+    let mut vectors = Vec::with_capacity(case.count);
+    let mut k = 0;
+    while vectors.len() < case.count {
+        let cats = [vector_cat(case.name, k, 0), vector_cat(case.name, k, 1)];
+        k += 1;
+        let lock_args = [&cats[0].0, &cats[1].0];
+        let lock_hashes = [&cats[0].1, &cats[1].1];
+        let mut input_nft = vec![cats[0].2.clone(), cats[1].2.clone()];
+        let stats: [Statistics; 2] = [input_nft[0].hash.into(), input_nft[1].hash.into()];
+        (case.prepare)(&mut input_nft, &stats, params);
+
+        let migrated: Vec<NFTData> = input_nft
+            .iter()
+            .map(|nft| nft.migrate(&vector_season()))
+            .collect();
+        //平局不能上链，跳过
+        let (n, output_nft) =
+            match gen_fight_result(&migrated, lock_hashes[0], lock_hashes[1], 3000, params) {
+                Some(result) => result,
+                None => continue,
+            };
+        if !(case.accept)(&migrated, &output_nft) {
+            continue;
+        }
+        let mut vector = json!({
+            "case": case.name,
+            "lock_args": [to_hex(lock_args[0]), to_hex(lock_args[1])],
+            "lock_hashes": [to_hex(lock_hashes[0]), to_hex(lock_hashes[1])],
+            "inputs": [to_hex(&input_nft[0].serialize()), to_hex(&input_nft[1].serialize())],
+            "stats": [stats_json(&stats[0]), stats_json(&stats[1])],
+            "hurts": [
+                params.hurt(&stats[0], &stats[1]).expect("hurt"),
+                params.hurt(&stats[1], &stats[0]).expect("hurt"),
+            ],
+            "rounds": n,
+            "winner": 1 - loser(&migrated, &output_nft),
+            "outputs": [to_hex(&output_nft[0].serialize()), to_hex(&output_nft[1].serialize())],
+        });
+        if let Some(error) = case.error {
+            vector["error"] = json!(error);
+        }
+        vectors.push(vector);
+    }
+    vectors
+}
+
+fn gen_fight_vectors() -> Value {
+    let params = Params::default();
+    let season = vector_season();
+    let vectors: Vec<Value> = cases()
+        .iter()
+        .flat_map(|case| gen_vectors(case, &params))
+        .collect();
+    json!({
+        "version": VECTORS_VERSION,
+        "params": {
+            "def_base": params.def_base,
+            "lck_factor": params.lck_factor,
+            "hp_factor": params.hp_factor,
+            "loser_divisor": params.loser_divisor,
+            "winner_divisor": params.winner_divisor,
+            "hidden_reward": params.hidden_reward,
+        },
+        "season": {
+            "number": season.number,
+            "base_fishes": season.base_fishes,
+        },
+        "vectors": vectors,
+    })
+}
+
+fn to_hex(data: &[u8]) -> String {
+    format!("0x{}", faster_hex::hex_string(data).unwrap())
+}
+
+fn from_hex(value: &Value) -> Vec<u8> {
+    let hex = value.as_str().expect("hex string").trim_start_matches("0x");
+    let mut buf = vec![0u8; hex.len() / 2];
+    faster_hex::hex_decode(hex.as_bytes(), &mut buf).expect("hex");
+    buf
+}
+
+fn stats_json(stats: &Statistics) -> Value {
+    json!({ "hp": stats.hp, "atk": stats.atk, "def": stats.def, "lck": stats.lck })
+}
+
+fn params_from_json(value: &Value) -> Params {
+    let field = |key: &str| value[key].as_i64().expect("param");
+    Params {
+        def_base: field("def_base") as u16,
+        lck_factor: field("lck_factor") as u8,
+        hp_factor: field("hp_factor") as u8,
+        loser_divisor: field("loser_divisor") as u8,
+        winner_divisor: field("winner_divisor") as u8,
+        hidden_reward: field("hidden_reward") as i32,
+    }
+}
+
+fn season_from_json(value: &Value) -> Season {
+    let field = |key: &str| value[key].as_i64().expect("season");
+    Season {
+        number: field("number") as u32,
+        base_fishes: field("base_fishes") as i32,
+    }
+}
+
+fn read_vectors() -> Value {
+    serde_json::from_str(&fs::read_to_string(VECTORS_PATH).expect("read vectors"))
+        .expect("parse vectors")
+}
+
+//提交的测试向量必须与生成器的结果一致，修改规则或生成器后重新生成：
+//UPDATE_FIGHT_VECTORS=1 cargo test test_fight_vectors_up_to_date
+#[test]
+fn test_fight_vectors_up_to_date() {
+    let doc = gen_fight_vectors();
+    if env::var(UPDATE_VECTORS_VAR).is_ok() {
+        fs::write(VECTORS_PATH, serde_json::to_string_pretty(&doc).unwrap())
+            .expect("write vectors");
+        return;
+    }
+    assert!(
+        read_vectors() == doc,
+        "{} is out of date, regenerate it with {}=1",
+        VECTORS_PATH,
+        UPDATE_VECTORS_VAR
+    );
+}
+
+//用合约重放每一个测试向量
+#[test]
+fn test_fight_vectors() {
+    let doc = read_vectors();
+    assert_eq!(doc["version"].as_u64(), Some(VECTORS_VERSION));
+    let params = params_from_json(&doc["params"]);
+    let season = season_from_json(&doc["season"]);
+
+    let mut context = Context::default();
+    let config = Config {
+        threshold: 1,
+        //测试中不需要管理员签名
+        admin_lock_hashes: vec![vec![0u8; 32]],
+        params: Some(params.clone()),
+        season: Some(season),
+        ..Default::default()
+    };
+    let env = CatEnv::new(&mut context, &config);
+    let vector_dep = deploy_vector_lock(&mut context);
+
+    let vectors = doc["vectors"].as_array().expect("vectors");
+    assert!(!vectors.is_empty());
+    for (i, vector) in vectors.iter().enumerate() {
+        let mut inputs = Vec::with_capacity(2);
+        let mut outputs = Vec::with_capacity(2);
+        let mut outputs_data = Vec::with_capacity(2);
+        let mut stats = Vec::with_capacity(2);
+        for j in 0..2 {
+            let lock = vector_lock(&from_hex(&vector["lock_args"][j]));
+            assert_eq!(
+                lock.calc_script_hash().as_slice(),
+                &from_hex(&vector["lock_hashes"][j])[..],
+                "vector {} lock hash",
+                i
+            );
+            let input_data = from_hex(&vector["inputs"][j]);
+            let mut hash = [0u8; 20];
+            hash.copy_from_slice(&input_data[16..36]);
+            let stat: Statistics = hash.into();
+            assert_eq!(stats_json(&stat), vector["stats"][j], "vector {} stats", i);
+            stats.push(stat);
+
+            let out_point = context.create_cell(env.cat_output(&lock), Bytes::from(input_data));
+            inputs.push(CellInput::new_builder().previous_output(out_point).build());
+            outputs.push(env.cat_output(&lock));
+            outputs_data.push(Bytes::from(from_hex(&vector["outputs"][j])));
+        }
+        assert_eq!(
+            json!([
+                params.hurt(&stats[0], &stats[1]).expect("hurt"),
+                params.hurt(&stats[1], &stats[0]).expect("hurt")
+            ]),
+            vector["hurts"],
+            "vector {} hurts",
+            i
+        );
+
+        let n = vector["rounds"].as_u64().expect("rounds") as u16;
+        let witnesses = vec![
+            Bytes::new(),
+            WitnessArgsBuilder::default()
+                .input_type(Some(Bytes::from(n.to_be_bytes().to_vec())).pack())
+                .build()
+                .as_bytes(),
+        ];
+        //lock用type hash引用代码，不经过complete_tx，直接给出所有cell dep
+        let tx = TransactionBuilder::default()
+            .inputs(inputs)
+            .outputs(outputs)
+            .outputs_data(outputs_data.pack())
+            .cell_deps(env.cell_deps.clone())
+            .cell_dep(vector_dep.clone())
+            .witnesses(witnesses.pack())
+            .build();
+        let result = context.verify_tx(&tx, MAX_CYCLES);
+        match vector["error"].as_i64() {
+            Some(code) => assert_script_error(result, code as i8),
+            None => {
+                if let Err(err) = result {
+                    panic!("vector {} failed: {:?}", i, err);
+                }
+            }
+        }
+    }
+}
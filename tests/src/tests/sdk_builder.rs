@@ -0,0 +1,273 @@
+use super::*;
+use ckb_testtool::{builtin::ALWAYS_SUCCESS, context::Context};
+use ckb_tool::ckb_types::{bytes::Bytes, core::TransactionView, packed::*, prelude::*};
+use sourly_cat_sdk as sdk;
+
+const REVIVE_FEE: u64 = 1000;
+const REVIVE_FISHES: i32 = 20;
+
+//admin是唯一的管理员，可以铸造和销毁
+fn gen_env(context: &mut Context, items: bool) -> (CatEnv, Script) {
+    let always_success_out_point = context.deploy_cell(ALWAYS_SUCCESS.clone());
+    let admin = context
+        .build_script(&always_success_out_point, random_20bytes())
+        .expect("lock script");
+    let config = Config {
+        threshold: 1,
+        admin_lock_hashes: vec![Vec::from(admin.calc_script_hash().as_slice())],
+        revive: Some(Revive {
+            fee: REVIVE_FEE,
+            fishes: REVIVE_FISHES,
+        }),
+        ..Default::default()
+    };
+    let env = if items {
+        CatEnv::with_items(context, &config)
+    } else {
+        CatEnv::new(context, &config)
+    };
+    (env, admin)
+}
+
+fn deployment(env: &CatEnv) -> sdk::CatDeployment {
+    sdk::CatDeployment {
+        cell_deps: env.cell_deps.clone(),
+        type_script: env.type_script.clone(),
+    }
+}
+
+//lock下的一只随机的猫
+fn gen_cat(context: &mut Context, env: &CatEnv, lock: &Script) -> sdk::CatCell {
+    let nft = NFTData::gen_random_nft(&Vec::from(lock.calc_script_hash().as_slice()));
+    sdk::CatCell {
+        input: env.create_cat(context, lock, &nft),
+        lock: lock.clone(),
+        capacity: 500,
+        data: nft.to_core(),
+    }
+}
+
+//lock下的普通cell，用作管理员或者支付费用的输入
+fn gen_input(context: &mut Context, lock: &Script) -> CellInput {
+    let out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+    CellInput::new_builder().previous_output(out_point).build()
+}
+
+//SDK只放空的见证，always success不检查签名，在输入0的见证的lock里放任意数据表示已签名
+fn sign(tx: TransactionView) -> TransactionView {
+    let mut witnesses: Vec<Bytes> = tx
+        .witnesses()
+        .into_iter()
+        .map(|witness| witness.unpack())
+        .collect();
+    witnesses[0] = WitnessArgsBuilder::default()
+        .lock(Some(Bytes::from(vec![0u8; SIGNATURE_SIZE])).pack())
+        .build()
+        .as_bytes();
+    tx.as_advanced_builder()
+        .set_witnesses(witnesses.iter().map(|witness| witness.pack()).collect())
+        .build()
+}
+
+//BuiltTx.cats必须与交易中猫的输出数据一一对应
+fn verify_built(context: &mut Context, env: &CatEnv, built: &sdk::BuiltTx, tx: TransactionView) {
+    let type_script = env.type_script.as_bytes();
+    let outputs: Vec<Bytes> = tx
+        .outputs_with_data_iter()
+        .filter(|(output, _)| {
+            output.type_().to_opt().map(|script| script.as_bytes()) == Some(type_script.clone())
+        })
+        .map(|(_, data)| data)
+        .collect();
+    let cats: Vec<Bytes> = built
+        .cats
+        .iter()
+        .map(|cat| Bytes::from(cat.serialize()))
+        .collect();
+    assert_eq!(outputs, cats);
+
+    let tx = context.complete_tx(tx);
+    let cycles = context
+        .verify_tx(&tx, MAX_CYCLES)
+        .expect("pass verification");
+    println!("consume cycles: {}", cycles);
+}
+
+#[test]
+fn test_sdk_mint() {
+    let mut context = Context::default();
+    let (env, admin) = gen_env(&mut context, false);
+    let lock = env.gen_lock(&mut context);
+    let built = sdk::MintBuilder::new(deployment(&env))
+        .owner_input(gen_input(&mut context, &admin))
+        .cat("Tom", lock.clone(), 500)
+        .and_then(|builder| builder.cat("Jerry", lock, 500))
+        .and_then(|builder| builder.build())
+        .expect("build mint");
+    assert_eq!(built.cats.len(), 2);
+    verify_built(&mut context, &env, &built, built.tx.clone());
+}
+
+#[test]
+fn test_sdk_transfer() {
+    let mut context = Context::default();
+    let (env, _) = gen_env(&mut context, false);
+    let locks = [env.gen_lock(&mut context), env.gen_lock(&mut context)];
+    let cats = [
+        gen_cat(&mut context, &env, &locks[0]),
+        gen_cat(&mut context, &env, &locks[1]),
+    ];
+    let built = sdk::TransferBuilder::new(deployment(&env))
+        .cat(cats[0].clone(), env.gen_lock(&mut context))
+        .cat(cats[1].clone(), env.gen_lock(&mut context))
+        .build()
+        .expect("build transfer");
+    assert_eq!(built.cats, vec![cats[0].data.clone(), cats[1].data.clone()]);
+    verify_built(&mut context, &env, &built, built.tx.clone());
+}
+
+#[test]
+fn test_sdk_rename() {
+    let mut context = Context::default();
+    let (env, _) = gen_env(&mut context, false);
+    let lock = env.gen_lock(&mut context);
+    let cat = gen_cat(&mut context, &env, &lock);
+    let built = sdk::RenameBuilder::new(deployment(&env), cat.clone(), "Tom", DEFAULT_RENAME_FEE)
+        .build()
+        .expect("build rename");
+    assert_eq!(built.cats[0].fishes, cat.data.fishes - DEFAULT_RENAME_FEE);
+    verify_built(&mut context, &env, &built, sign(built.tx.clone()));
+}
+
+#[test]
+fn test_sdk_revive() {
+    let mut context = Context::default();
+    let (env, _) = gen_env(&mut context, false);
+    let lock = env.gen_lock(&mut context);
+    let mut cat = gen_cat(&mut context, &env, &lock);
+    let mut nft = NFTData::from(&cat.data);
+    nft.fishes = -5;
+    cat.input = env.create_cat(&mut context, &lock, &nft);
+    cat.data = nft.to_core();
+
+    //复活费付给配置cell的lock，由猫的主人支付
+    let built = sdk::ReviveBuilder::new(
+        deployment(&env),
+        cat,
+        env.revive.clone(),
+        env.owner_lock.clone(),
+    )
+    .build()
+    .expect("build revive");
+    assert_eq!(built.cats[0].fishes, REVIVE_FISHES);
+    let tx = built
+        .tx
+        .as_advanced_builder()
+        .input(gen_input(&mut context, &lock))
+        .witness(Bytes::new().pack())
+        .build();
+    verify_built(&mut context, &env, &built, sign(tx));
+}
+
+#[test]
+fn test_sdk_fight() {
+    let mut context = Context::default();
+    let (env, _) = gen_env(&mut context, false);
+    let locks = [env.gen_lock(&mut context), env.gen_lock(&mut context)];
+    //平局的话重新生成
+    let (built, result) = loop {
+        let builder = sdk::FightBuilder::new(
+            deployment(&env),
+            gen_cat(&mut context, &env, &locks[0]),
+            gen_cat(&mut context, &env, &locks[1]),
+        )
+        .params(env.params.clone())
+        .season(env.season.clone());
+        match builder.build() {
+            Ok(built) => break built,
+            Err(sdk::Error::Draw) => continue,
+            Err(err) => panic!("build fight: {}", err),
+        }
+    };
+    assert_eq!(built.cats[..], result.outputs[..]);
+    verify_built(&mut context, &env, &built, built.tx.clone());
+}
+
+#[test]
+fn test_sdk_fight_with_items() {
+    let mut context = Context::default();
+    let (env, _) = gen_env(&mut context, true);
+    //猫0带着项圈，猫1喝掉一瓶药水
+    let locks = [env.gen_lock(&mut context), env.gen_lock(&mut context)];
+    let item_output = |lock: &Script| {
+        CellOutput::new_builder()
+            .capacity(500u64.pack())
+            .lock(lock.clone())
+            .type_(Some(env.item_type.clone()).pack())
+            .build()
+    };
+    let item = |kind| sdk::Item {
+        kind,
+        bonus: Statistics {
+            hp: 0,
+            atk: 50,
+            def: 50,
+            lck: 0,
+        },
+    };
+    let (built, result) = loop {
+        let collar = context.create_cell(
+            item_output(&locks[0]),
+            Bytes::from(item(ITEM_KIND_COLLAR).serialize().to_vec()),
+        );
+        let potion = context.create_cell(
+            item_output(&locks[1]),
+            Bytes::from(item(ITEM_KIND_POTION).serialize().to_vec()),
+        );
+        let builder = sdk::FightBuilder::new(
+            deployment(&env),
+            gen_cat(&mut context, &env, &locks[0]),
+            gen_cat(&mut context, &env, &locks[1]),
+        )
+        .params(env.params.clone())
+        .season(env.season.clone())
+        .equip(
+            0,
+            CellDep::new_builder().out_point(collar).build(),
+            item(ITEM_KIND_COLLAR),
+        )
+        .drink(
+            1,
+            CellInput::new_builder().previous_output(potion).build(),
+            item(ITEM_KIND_POTION),
+        );
+        match builder.build() {
+            Ok(built) => break built,
+            Err(sdk::Error::Draw) => continue,
+            Err(err) => panic!("build fight: {}", err),
+        }
+    };
+    assert_eq!(built.cats[..], result.outputs[..]);
+    verify_built(&mut context, &env, &built, built.tx.clone());
+}
+
+#[test]
+fn test_sdk_burn() {
+    let mut context = Context::default();
+    let (env, admin) = gen_env(&mut context, false);
+    let lock = env.gen_lock(&mut context);
+    let cat = gen_cat(&mut context, &env, &lock);
+    let built = sdk::BurnBuilder::new(deployment(&env))
+        .owner_input(gen_input(&mut context, &admin))
+        .cat(cat)
+        .build()
+        .expect("build burn");
+    assert!(built.cats.is_empty());
+    verify_built(&mut context, &env, &built, built.tx.clone());
+}
@@ -0,0 +1,98 @@
+use super::*;
+use rand::{rngs::StdRng, SeedableRng};
+use sourly_cat_simulator::sim::{parse_values, random_cat, simulate, Report};
+
+const SEED: u64 = 42;
+const SAMPLES: u32 = 200;
+const FISHES: i32 = 100;
+const MAX_ROUNDS: u16 = 5000;
+
+fn run_simulate(params: &Params) -> Report {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    simulate(&mut rng, params, SAMPLES, FISHES, MAX_ROUNDS).expect("simulate")
+}
+
+#[test]
+fn test_simulate_deterministic() {
+    let params = Params::default();
+    assert_eq!(run_simulate(&params), run_simulate(&params));
+}
+
+//用同一个种子生成同样的猫，直接调用battle::resolve统计，结果必须与模拟器一致
+#[test]
+fn test_simulate_agrees_with_resolve() {
+    let params = Params::default();
+    let season = Season::default();
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let mut wins = [0u32; 2];
+    let mut draws = 0u32;
+    let mut hidden_rewards = 0u32;
+    let mut rounds = 0u64;
+    let mut inflation = 0i64;
+    for _ in 0..SAMPLES {
+        let (cat_0, lock_hash_0) = random_cat(&mut rng, FISHES);
+        let (cat_1, lock_hash_1) = random_cat(&mut rng, FISHES);
+        let inputs = [
+            cat_0.migrate(&season).expect("migrate"),
+            cat_1.migrate(&season).expect("migrate"),
+        ];
+        let stats = [inputs[0].hash.into(), inputs[1].hash.into()];
+        match battle::resolve(
+            &inputs,
+            [&lock_hash_0, &lock_hash_1],
+            &stats,
+            &params,
+            MAX_ROUNDS,
+        ) {
+            Ok(result) => {
+                wins[result.winner] += 1;
+                rounds += result.rounds as u64;
+                let before: i64 = inputs.iter().map(|cat| cat.fishes as i64).sum();
+                let after: i64 = result.outputs.iter().map(|cat| cat.fishes as i64).sum();
+                inflation += after - before;
+                if result.outputs[1 - result.winner].badges & BADGE_HIDDEN_REWARD != 0 {
+                    hidden_rewards += 1;
+                }
+            }
+            Err(CoreError::Draw) => draws += 1,
+            Err(err) => panic!("resolve: {:?}", err),
+        }
+    }
+
+    let report = run_simulate(&params);
+    let decided = (wins[0] + wins[1]).max(1) as f64;
+    assert_eq!(report.samples, SAMPLES);
+    assert_eq!(report.challenger_win_rate, wins[0] as f64 / SAMPLES as f64);
+    assert_eq!(report.defender_win_rate, wins[1] as f64 / SAMPLES as f64);
+    assert_eq!(report.draw_rate, draws as f64 / SAMPLES as f64);
+    assert_eq!(report.avg_rounds, rounds as f64 / decided);
+    assert_eq!(report.fish_inflation, inflation as f64 / decided);
+    assert_eq!(
+        report.hidden_reward_rate,
+        hidden_rewards as f64 / SAMPLES as f64
+    );
+}
+
+#[test]
+fn test_parse_values() {
+    assert_eq!(parse_values::<u8>("1..4").unwrap(), vec![1, 2, 3, 4]);
+    assert_eq!(parse_values::<u8>(" 2 .. 2 ").unwrap(), vec![2]);
+    assert_eq!(
+        parse_values::<u16>("250, 500,1000").unwrap(),
+        vec![250, 500, 1000]
+    );
+    assert_eq!(parse_values::<u8>("7").unwrap(), vec![7]);
+}
+
+#[test]
+fn test_parse_values_invalid() {
+    //超出类型的范围
+    assert!(parse_values::<u8>("256").is_err());
+    assert!(parse_values::<u8>("250..260").is_err());
+    //不是数字
+    assert!(parse_values::<u8>("a").is_err());
+    assert!(parse_values::<u8>("1,,2").is_err());
+    assert!(parse_values::<u8>("-1").is_err());
+    assert!(parse_values::<u8>("1..").is_err());
+    assert!(parse_values::<u8>("").is_err());
+}